@@ -4,11 +4,52 @@ pub const SECONDS_IN_A_DAY: i64 = 60 * 60 * 24;
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 const TOKEN_DEICMALS_HELPER: u64 = 1_000_000_000; // 6 decimals
 pub const MIN_INITIALIZE_TOKEN_AMOUNT: u64 = 1 * LAMPORTS_PER_SOL;
-pub const INITIAL_BURN_TOKEN_AMOUNT: u64 = 10_000 * TOKEN_DEICMALS_HELPER;
+pub const MAX_INITIAL_BURN_TOKEN_AMOUNT: u64 = 10_000 * TOKEN_DEICMALS_HELPER; // 10,000,000 tokens at the mint's 6 decimals
 
 pub const MIN: u64 = 1000;
 pub const FEE_BASE_1000: u64 = 1000;
-pub const FEES_BUY: u64 = 100;
-pub const FEES_BUY_REFERRAL: u64 = 25;
-pub const FEES_SELL: u64 = 125;
+pub const FEE_BASE_10000: u64 = 10_000;
+pub const MAX_TREASURY_FEE_BPS: u64 = 250; // 2.5%
+pub const MAX_REFERRAL_FEE_BPS: u64 = 100; // 1%
+pub const MIN_BACKING_FEE_BPS: u64 = 80; // 0.8%
+pub const MAX_BACKING_FEE_BPS: u64 = 250; // 2.5%
+pub const MAX_TOTAL_TRADE_FEE_BPS: u64 = 500; // 5%
+pub const MAX_FEE_TIERS: usize = 4;
+pub const MAX_FEE_TIER_DISCOUNT_BPS: u64 = 5_000; // 50% of the treasury fee
+pub const VOLUME_WINDOW_DAYS: usize = 30;
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+pub const MAX_ADMINS: usize = 5;
+pub const PAUSE_BUY: u8 = 1 << 0;
+pub const PAUSE_SELL: u8 = 1 << 1;
+pub const PAUSE_NEW_LOANS: u8 = 1 << 2;
+pub const PAUSE_LOAN_MODIFICATIONS: u8 = 1 << 3;
+pub const PAUSE_LIQUIDATION: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 = PAUSE_BUY | PAUSE_SELL | PAUSE_NEW_LOANS | PAUSE_LOAN_MODIFICATIONS | PAUSE_LIQUIDATION;
+pub const MAX_BUY_FEE_LEVERAGE: u64 = 25; // 2.5%
+pub const MAX_REFERRAL_BINDING_DURATION: u64 = 365 * SECONDS_IN_A_DAY as u64;
+pub const MAX_CONFIG_TIMELOCK: u64 = 30 * SECONDS_IN_A_DAY as u64;
+pub const RELAUNCH_DELAY: i64 = 7 * SECONDS_IN_A_DAY;
+pub const MIN_TOKENS_PER_SOL: u64 = 1_000_000; // 1 token
+pub const MAX_TOKENS_PER_SOL: u64 = 1_000_000_000_000_000_000; // 1e12 tokens
+pub const MAX_TEAM_ALLOCATION_BPS: u64 = 2_000; // 20% of the initial mint
+pub const MAX_SELLER_FEE_BPS: u16 = 1_000; // 10%
+pub const MAX_TEAM_VESTING_DURATION: i64 = 4 * 365 * SECONDS_IN_A_DAY;
+pub const MAX_TRADING_START_DELAY: i64 = 30 * SECONDS_IN_A_DAY;
+pub const MAX_ANTI_BOT_DURATION: i64 = SECONDS_IN_A_DAY;
+pub const MAX_REFERRAL_FEE_SHARE: u64 = 500;
+pub const MAX_INTEGRATOR_FEE_BPS: u64 = 100;
+pub const MIN_REFERRAL_CODE_LEN: usize = 3;
+pub const MAX_REFERRAL_CODE_LEN: usize = 16;
+pub const MIN_PROTOCOL_FEE_SHARE: u64 = 100; // 10% of interest and flash close fees
+pub const MAX_PROTOCOL_FEE_SHARE: u64 = 500; // 50% of interest and flash close fees
+pub const MIN_FLASH_CLOSE_FEE: u64 = 5; // 0.5%
+pub const MAX_FLASH_CLOSE_FEE: u64 = 25; // 2.5%
+pub const DEFAULT_DAILY_TRADE_FEE_CHANGE_BPS: u64 = 50; // 0.5% per trade fee
+pub const DEFAULT_DAILY_BUY_FEE_LEVERAGE_CHANGE: u64 = 5; // 0.5%
+pub const DEFAULT_DAILY_PROTOCOL_FEE_SHARE_CHANGE: u64 = 100; // 10% of interest and flash close fees
+pub const DEFAULT_DAILY_FLASH_CLOSE_FEE_CHANGE: u64 = 5; // 0.5%
+pub const MAX_DAILY_TRADE_FEE_CHANGE_BPS: u64 = 200; // 2% per trade fee
+pub const MAX_DAILY_BUY_FEE_LEVERAGE_CHANGE: u64 = 10; // 1%
+pub const MAX_DAILY_PROTOCOL_FEE_SHARE_CHANGE: u64 = 200; // 20% of interest and flash close fees
+pub const MAX_DAILY_FLASH_CLOSE_FEE_CHANGE: u64 = 10; // 1%
 pub const MAX_SUPPLY: u128 = 10_000_000_000_000_000_000_000_000_000; // 10e28
\ No newline at end of file
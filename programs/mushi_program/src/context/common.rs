@@ -1,8 +1,8 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::keccak::hashv};
 use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
-    constants::{FEE_BASE_1000, LAMPORTS_PER_SOL, SECONDS_IN_A_DAY, VAULT_SEED}, error::MushiProgramError, state::{GlobalStats, MainState, UserLoan}, utils::{get_date_from_timestamp, get_date_string_from_timestamp, get_interest_fee}, DailyStats 
+    constants::{FEE_BASE_1000, LAMPORTS_PER_SOL, SECONDS_IN_A_DAY, VAULT_SEED}, error::MushiProgramError, state::{GlobalStats, LaunchBuyer, MainState, Promotion, ReferralCode, Referrer, UserLoan, UserStats}, utils::{verify_merkle_proof, get_bps_fee, get_date_from_timestamp, get_date_string_from_timestamp, get_interest_fee, transfer_sol}, DailyStats 
 };
 
 #[derive(Accounts)]
@@ -54,11 +54,42 @@ pub struct ACommon<'info> {
         bump
     )]
     pub user_loan: Box<Account<'info, UserLoan>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::MAX_SIZE,
+        seeds = [
+            UserStats::PREFIX_SEED,
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+    #[account(
+        mut,
+        seeds = [Promotion::PREFIX_SEED],
+        bump,
+    )]
+    pub promotion: Option<Box<Account<'info, Promotion>>>,
+    /// Required when the user is bound to a referrer
     #[account(
         mut,
-        address=main_state.fee_receiver,
+        seeds = [Referrer::PREFIX_SEED, referrer.authority.as_ref()],
+        bump,
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+    /// Required to buy during the anti-bot window and the presale
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + LaunchBuyer::MAX_SIZE,
+        seeds = [LaunchBuyer::PREFIX_SEED, user.key().as_ref()],
+        bump,
     )]
-    pub fee_receiver:SystemAccount<'info>,
+    pub launch_buyer: Option<Box<Account<'info, LaunchBuyer>>>,
+    /// CHECK: frontend or aggregator receiving the integrator fee, required when one is charged
+    #[account(mut)]
+    pub integrator: Option<UncheckedAccount<'info>>,
     #[account(
         mut,
         address = global_state.token,
@@ -101,7 +132,7 @@ impl<'info> ACommon<'info> {
     }
 
     pub fn get_backing(&self, sol_amount: u64) -> Result<u64> {
-        Ok(self.global_state.total_borrowed + self.token_vault_owner.lamports() + sol_amount)
+        Ok(self.global_state.total_borrowed + self.global_state.backing + sol_amount)
     }
     pub fn sol_to_mushi(&self, sol_amount: u64) -> Result<u64>{
         Ok(
@@ -153,8 +184,73 @@ impl<'info> ACommon<'info> {
             MushiProgramError::SafetyCheckPriceFailed
         );
         self.global_state.last_price = new_price;
+        self.stamp_versions();
         Ok(())
     }
+
+    pub fn in_anti_bot_window(&self) -> Result<bool> {
+        Ok(Clock::get()?.unix_timestamp < self.global_state.anti_bot_end_ts)
+    }
+
+    pub fn require_trading_started(&self) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.global_state.trading_start_ts,
+            MushiProgramError::TradingNotStarted
+        );
+        Ok(())
+    }
+
+    /// During the anti-bot window, allows a single buy of at most `anti_bot_max_buy` per wallet
+    pub fn check_anti_bot_limit(&mut self, sol_amount: u64) -> Result<()> {
+        if !self.in_anti_bot_window()? {
+            return Ok(());
+        }
+        let max_buy = self.global_state.anti_bot_max_buy;
+        let launch_buyer = self.launch_buyer.as_mut().ok_or(MushiProgramError::LaunchBuyerNotFound)?;
+        require!(
+            launch_buyer.anti_bot_bought == 0 && sol_amount <= max_buy,
+            MushiProgramError::AntiBotLimitExceeded
+        );
+        launch_buyer.anti_bot_bought = sol_amount;
+        Ok(())
+    }
+
+    /// Checks the user's allowlist entry, a leaf hashing the user and its cap in lamports,
+    /// and records the presale buy against that cap
+    pub fn check_presale_buy(&mut self, sol_amount: u64, max_buy: u64, proof: &[[u8; 32]]) -> Result<()> {
+        let root = self.main_state.presale_root;
+        require!(
+            root != [0; 32]
+                && self.global_state.started
+                && Clock::get()?.unix_timestamp < self.global_state.trading_start_ts,
+            MushiProgramError::PresaleNotActive
+        );
+        let leaf = hashv(&[self.user.key.as_ref(), &max_buy.to_le_bytes()]).0;
+        require!(verify_merkle_proof(proof, root, leaf), MushiProgramError::NotAllowlisted);
+        let launch_buyer = self.launch_buyer.as_mut().ok_or(MushiProgramError::LaunchBuyerNotFound)?;
+        launch_buyer.presale_bought += sol_amount;
+        require!(launch_buyer.presale_bought <= max_buy, MushiProgramError::PresaleCapExceeded);
+        Ok(())
+    }
+
+    /// Sets the layout version of the accounts `init_if_needed` may have created,
+    /// older layouts are only upgraded by the `migrate_*` instructions
+    fn stamp_versions(&mut self) {
+        if self.daily_state.version == 0 {
+            self.daily_state.version = DailyStats::VERSION;
+        }
+        if self.last_liquidation_date_state.version == 0 {
+            self.last_liquidation_date_state.version = DailyStats::VERSION;
+        }
+        if self.user_loan.version == 0 {
+            self.user_loan.version = UserLoan::VERSION;
+        }
+        if let Some(launch_buyer) = self.launch_buyer.as_mut() {
+            if launch_buyer.version == 0 {
+                launch_buyer.version = LaunchBuyer::VERSION;
+            }
+        }
+    }
     pub fn is_loan_expired(&self) -> Result<bool> {
         let end_date = self.user_loan.end_date;
         let current_date = Clock::get()?.unix_timestamp;
@@ -164,17 +260,182 @@ impl<'info> ACommon<'info> {
     pub fn leverage_fee(&self, sol_amount: u64, number_of_days: u64) -> Result<u64> {
         let buy_fee_leverage = self.main_state.buy_fee_leverage;
         let mint_fee = sol_amount.checked_mul(buy_fee_leverage).unwrap().checked_div(FEE_BASE_1000).unwrap();
-        let interest = get_interest_fee(sol_amount, number_of_days);
+        let interest = self.interest_fee(sol_amount, number_of_days)?;
         Ok(mint_fee.checked_add(interest).unwrap())
     }
+
+    fn active_promotion(&self) -> Result<Option<&Promotion>> {
+        let now = Clock::get()?.unix_timestamp;
+        Ok(self.promotion.as_deref().map(|promotion| &**promotion).filter(|promotion| promotion.is_active(now)))
+    }
+
+    pub fn buy_treasury_bps(&self) -> Result<u64> {
+        let treasury_bps = self.main_state.fee_schedule.buy_treasury_bps;
+        Ok(self.active_promotion()?.map_or(treasury_bps, |promotion| promotion.buy_treasury_bps.min(treasury_bps)))
+    }
+
+    pub fn sell_treasury_bps(&self) -> Result<u64> {
+        let treasury_bps = self.main_state.fee_schedule.sell_treasury_bps;
+        Ok(self.active_promotion()?.map_or(treasury_bps, |promotion| promotion.sell_treasury_bps.min(treasury_bps)))
+    }
+
+    /// Returns the interest fee of a loan, reduced while a promotion is running
+    pub fn interest_fee(&self, sol_amount: u64, number_of_days: u64) -> Result<u64> {
+        let interest = get_interest_fee(sol_amount, number_of_days);
+        Ok(self.active_promotion()?.map_or(interest, |promotion| get_bps_fee(interest, promotion.interest_fee_bps)))
+    }
+
+    pub fn announce_promotion(&mut self) -> Result<()> {
+        if let Some(promotion) = self.promotion.as_mut() {
+            promotion.announce(Clock::get()?.unix_timestamp);
+        }
+        Ok(())
+    }
+
+    /// Returns the part of an interest or flash close fee owed to the protocol,
+    /// the rest of the fee stays in the vault as backing
+    pub fn protocol_fee(&self, fee: u64) -> u64 {
+        fee.checked_mul(self.main_state.protocol_fee_share).unwrap().checked_div(FEE_BASE_1000).unwrap()
+    }
+
+    pub fn flash_close_fee(&self, collateral_in_sol: u64) -> u64 {
+        collateral_in_sol.checked_mul(self.main_state.flash_close_fee).unwrap().checked_div(FEE_BASE_1000).unwrap()
+    }
+
+    /// Applies the volume tier discount of the user to a treasury fee
+    pub fn discounted_treasury_fee(&self, treasury_fee: u64) -> Result<u64> {
+        let volume = self.user_stats.window_volume(Clock::get()?.unix_timestamp);
+        let discount_bps = self.main_state.fee_discount_bps(volume);
+        Ok(treasury_fee - get_bps_fee(treasury_fee, discount_bps))
+    }
+
+    pub fn record_volume(&mut self, volume: u64) -> Result<()> {
+        self.user_stats.record_volume(volume, Clock::get()?.unix_timestamp);
+        Ok(())
+    }
+
+    /// Keeps a protocol fee received by the vault aside from the backing until it is claimed
+    pub fn accrue_protocol_fee(&mut self, protocol_fee: u64) {
+        self.global_state.accrued_fees = self.global_state.accrued_fees.checked_add(protocol_fee).unwrap();
+    }
+
+    /// Whether the user is bound to a referrer whose binding has not expired yet
+    pub fn is_referral_bound(&self) -> Result<bool> {
+        if self.user_stats.referrer == Pubkey::default() {
+            return Ok(false);
+        }
+        let duration = self.main_state.referral_binding_duration;
+        Ok(duration == 0 || Clock::get()?.unix_timestamp < self.user_stats.referral_bound_at + duration as i64)
+    }
+
+    /// Binds the user to the passed referrer, unless they are still bound to it or to another one
+    pub fn bind_referrer(&mut self) -> Result<()> {
+        let authority = self.referrer.as_ref().ok_or(MushiProgramError::ReferralNotFound)?.authority;
+        require!(authority != self.user.key(), MushiProgramError::SelfReferral);
+        if self.is_referral_bound()? {
+            require!(self.user_stats.referrer == authority, MushiProgramError::ReferrerAlreadyBound);
+            return Ok(());
+        }
+        // rebinding to the same referrer after the binding expired is not a new referee
+        let is_new_referee = self.user_stats.referrer != authority;
+        self.user_stats.referrer = authority;
+        self.user_stats.referral_bound_at = Clock::get()?.unix_timestamp;
+        if is_new_referee {
+            self.referrer.as_mut().unwrap().referee_count += 1;
+        }
+        Ok(())
+    }
+
+    /// Whether the user's trades are credited to a referrer, checking the passed referrer account is the bound one
+    pub fn has_bound_referrer(&self) -> Result<bool> {
+        if !self.is_referral_bound()? {
+            return Ok(false);
+        }
+        let referrer = self.referrer.as_ref().ok_or(MushiProgramError::ReferralNotFound)?;
+        require!(referrer.authority == self.user_stats.referrer, MushiProgramError::InvalidReferralAccount);
+        Ok(true)
+    }
+
+    /// Credits a referred trade to the bound referrer, keeping its reward in the vault until it is claimed
+    pub fn credit_referrer(&mut self, volume: u64, reward: u64) -> Result<()> {
+        let referrer = self.referrer.as_mut().ok_or(MushiProgramError::ReferralNotFound)?;
+        referrer.referred_volume = referrer.referred_volume.checked_add(volume).unwrap();
+        referrer.total_earned = referrer.total_earned.checked_add(reward).unwrap();
+        referrer.pending_rewards = referrer.pending_rewards.checked_add(reward).unwrap();
+        self.global_state.referral_rewards = self.global_state.referral_rewards.checked_add(reward).unwrap();
+        Ok(())
+    }
+
+    /// Credits the trade to the user's referrer when they are bound to one
+    pub fn credit_bound_referrer(&mut self, volume: u64, reward: u64) -> Result<()> {
+        if self.has_bound_referrer()? {
+            self.credit_referrer(volume, reward)?;
+        }
+        Ok(())
+    }
+
+    /// Accrues a loan protocol fee, paying `referral_share` (FEE_BASE_1000) of it to the user's referrer
+    pub fn accrue_loan_fee(&mut self, protocol_fee: u64, volume: u64, referral_share: u64) -> Result<()> {
+        let reward = if self.has_bound_referrer()? {
+            protocol_fee * referral_share / FEE_BASE_1000
+        } else {
+            0
+        };
+        self.accrue_protocol_fee(protocol_fee - reward);
+        self.credit_bound_referrer(volume, reward)
+    }
+
+    /// Returns the integrator fee of a trade, checking it against the configured maximum
+    pub fn integrator_fee(&self, sol_amount: u64, integrator_fee_bps: u64) -> Result<u64> {
+        if integrator_fee_bps == 0 {
+            return Ok(0);
+        }
+        require!(
+            integrator_fee_bps <= self.main_state.max_integrator_fee_bps,
+            MushiProgramError::InvalidIntegratorFee
+        );
+        require!(self.integrator.is_some(), MushiProgramError::IntegratorNotFound);
+        Ok(get_bps_fee(sol_amount, integrator_fee_bps))
+    }
+
+    /// Pays the integrator fee from the user, or from the vault when `signer_seeds` are passed
+    pub fn pay_integrator_fee(&self, fee: u64, signer_seeds: Option<&[&[&[u8]]]>) -> Result<()> {
+        if fee == 0 {
+            return Ok(());
+        }
+        let sender = match signer_seeds {
+            Some(_) => self.token_vault_owner.to_account_info(),
+            None => self.user.to_account_info(),
+        };
+        transfer_sol(
+            sender,
+            self.integrator.as_ref().unwrap().to_account_info(),
+            self.system_program.to_account_info(),
+            fee,
+            signer_seeds,
+        )
+    }
+
+    /// Records SOL that entered the vault as backing
+    pub fn add_backing(&mut self, sol_amount: u64) {
+        self.global_state.backing = self.global_state.backing.checked_add(sol_amount).unwrap();
+    }
+
+    /// Records SOL that left the vault's backing
+    pub fn sub_backing(&mut self, sol_amount: u64) {
+        self.global_state.backing = self.global_state.backing.checked_sub(sol_amount).unwrap();
+    }
 }
 
 #[derive(Accounts)]
 pub struct ACommonExtReferral<'info> {
     pub common: ACommon<'info>, // Embed the existing ACommon struct
-    
-    #[account(mut)]
-    pub referral: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [ReferralCode::PREFIX_SEED, referral_code.code_bytes()],
+        bump,
+    )]
+    pub referral_code: Option<Account<'info, ReferralCode>>,
 }
 
 #[derive(Accounts)]
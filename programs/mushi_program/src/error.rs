@@ -12,10 +12,10 @@ pub enum MushiProgramError {
     #[msg("amount is too small")]
     TooSmallInputAmount,
 
-    #[msg("buy fee must be greater than FEES_BUY and be less than 2.5%")]
+    #[msg("buy fees exceed the fee schedule caps")]
     InvalidBuyFee,
 
-    #[msg("sell fee must be greater than FEES_SELL and be less than 2.5%")]
+    #[msg("sell fees exceed the fee schedule caps")]
     InvalidSellFee,
 
     #[msg("leverage buy fee must be less 2.5%")]
@@ -58,4 +58,139 @@ pub enum MushiProgramError {
 
     #[msg("invalid referral account")]
     InvalidReferralAccount,
+
+    #[msg("protocol fee share must be between 10% and 50%")]
+    InvalidProtocolFeeShare,
+
+    #[msg("flash close fee must be between 0.5% and 2.5%")]
+    InvalidFlashCloseFee,
+
+    #[msg("fee tiers must have increasing volumes and discounts of at most 50%")]
+    InvalidFeeTiers,
+
+    #[msg("invalid promotion")]
+    InvalidPromotion,
+
+    #[msg("a promotion is already in progress")]
+    PromotionInProgress,
+
+    #[msg("fee recipients must be distinct and their weights must sum to 100%")]
+    InvalidFeeRecipients,
+
+    #[msg("fee recipient accounts do not match the configured recipients")]
+    InvalidFeeRecipientAccount,
+
+    #[msg("no fees to claim")]
+    NothingToClaim,
+
+    #[msg("no stray lamports to sync")]
+    NothingToSync,
+
+    #[msg("users cannot refer themselves")]
+    SelfReferral,
+
+    #[msg("user is already bound to another referrer")]
+    ReferrerAlreadyBound,
+
+    #[msg("referral fee shares must be at most 50% of the protocol fee")]
+    InvalidReferralFeeShare,
+
+    #[msg("referral codes must be 3 to 16 characters among a-z, 0-9, '-' and '_'")]
+    InvalidReferralCode,
+
+    #[msg("integrator fee exceeds the configured maximum")]
+    InvalidIntegratorFee,
+
+    #[msg("integrator account not found")]
+    IntegratorNotFound,
+
+    #[msg("no admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[msg("admins must be distinct and the threshold between 1 and their count")]
+    InvalidAdmins,
+
+    #[msg("not enough admins signed")]
+    NotEnoughAdminSigners,
+
+    #[msg("this operation is paused")]
+    Paused,
+
+    #[msg("configuration changes must go through the timelock")]
+    ConfigTimelocked,
+
+    #[msg("the configuration change cannot be executed before its eta")]
+    ConfigChangeNotReady,
+
+    #[msg("fee change exceeds the daily limit")]
+    FeeChangeTooLarge,
+
+    #[msg("fee recipients cannot be the vault or the main state")]
+    InvalidFeeRecipientAddress,
+
+    #[msg("referral binding duration must be at most 365 days")]
+    InvalidReferralBindingDuration,
+
+    #[msg("config timelock must be at most 30 days")]
+    InvalidConfigTimelock,
+
+    #[msg("already started")]
+    AlreadyStarted,
+
+    #[msg("a relaunch is already scheduled")]
+    RelaunchAlreadyScheduled,
+
+    #[msg("no relaunch is scheduled")]
+    RelaunchNotScheduled,
+
+    #[msg("the relaunch cannot be executed before its eta")]
+    RelaunchNotReady,
+
+    #[msg("cannot relaunch while loans are outstanding")]
+    LoansOutstanding,
+
+    #[msg("initial price must mint at least 1000 tokens for the SOL deposited")]
+    InvalidInitialPrice,
+
+    #[msg("burn amount exceeds the initial mint or 10,000,000 tokens")]
+    InvalidBurnAmount,
+
+    #[msg("team allocation must be at most 20% of the initial mint")]
+    InvalidTeamAllocation,
+
+    #[msg("seller fee must be at most 10%")]
+    InvalidSellerFee,
+
+    #[msg("team vesting must last between the cliff and 4 years")]
+    InvalidVestingSchedule,
+
+    #[msg("trading must start within 30 days and the anti-bot window last at most a day")]
+    InvalidLaunchSchedule,
+
+    #[msg("trading has not started yet")]
+    TradingNotStarted,
+
+    #[msg("only one buy up to the anti-bot limit per wallet during the anti-bot window")]
+    AntiBotLimitExceeded,
+
+    #[msg("launch buyer account not found")]
+    LaunchBuyerNotFound,
+
+    #[msg("the presale is not open")]
+    PresaleNotActive,
+
+    #[msg("wallet is not in the presale allowlist")]
+    NotAllowlisted,
+
+    #[msg("presale buy exceeds the wallet's cap")]
+    PresaleCapExceeded,
+
+    #[msg("fee change limits exceed their hard caps")]
+    InvalidFeeChangeLimits,
+
+    #[msg("account layout version is not supported")]
+    InvalidAccountVersion,
+
+    #[msg("cannot relaunch while tokens are held outside the vault and the team vesting")]
+    SupplyOutstanding,
 }
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Role;
+
+#[event]
+pub struct PromotionScheduled {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub buy_treasury_bps: u64,
+    pub sell_treasury_bps: u64,
+    pub interest_fee_bps: u64,
+}
+
+#[event]
+pub struct PromotionStarted {
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+#[event]
+pub struct PromotionEnded {
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+#[event]
+pub struct BackingDonated {
+    pub donor: Pubkey,
+    pub sol_amount: u64,
+}
+
+#[event]
+pub struct BackingSynced {
+    pub sol_amount: u64,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub pending_admins: Vec<Pubkey>,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admins: Vec<Pubkey>,
+    pub admins: Vec<Pubkey>,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub pending_admins: Vec<Pubkey>,
+}
+
+#[event]
+pub struct ConfigChangeProposed {
+    pub eta: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub eta: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub eta: i64,
+}
+
+#[event]
+pub struct Paused {
+    pub flags: u8,
+    pub pause_flags: u8,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct Unpaused {
+    pub flags: u8,
+    pub pause_flags: u8,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Pubkey,
+    pub previous: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Pubkey,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct RelaunchScheduled {
+    pub eta: i64,
+}
+
+#[event]
+pub struct RelaunchCancelled {
+    pub eta: i64,
+}
+
+#[event]
+pub struct Relaunched {
+    pub previous_token: Pubkey,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub claimed: u64,
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_ADMINS,
+    error::MushiProgramError,
+    events::{AdminTransferCancelled, AdminTransferProposed, AdminTransferred},
+    state::MainState,
+};
+
+/// First step of an admin set transfer, every new admin has to sign `accept_admins`
+pub fn propose_admins(ctx: Context<AAdminTransfer>, admins: Vec<Pubkey>, threshold: u64) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let main_state = &mut ctx.accounts.main_state;
+    main_state.pending_admins = MainState::to_admin_set(&admins, threshold)?;
+    main_state.pending_admin_threshold = threshold;
+
+    emit!(AdminTransferProposed {
+        pending_admins: admins,
+        threshold,
+    });
+    Ok(())
+}
+
+/// Signed by `pending_admin` and the other pending admins, passed as remaining accounts
+pub fn accept_admins(ctx: Context<AAcceptAdmins>) -> Result<()> {
+    let main_state = &mut ctx.accounts.main_state;
+    let signers: Vec<Pubkey> = std::iter::once(ctx.accounts.pending_admin.key())
+        .chain(
+            ctx.remaining_accounts
+                .iter()
+                .filter(|account| account.is_signer)
+                .map(|account| *account.key),
+        )
+        .collect();
+    require!(
+        main_state
+            .pending_admins
+            .iter()
+            .filter(|admin| **admin != Pubkey::default())
+            .all(|admin| signers.contains(admin)),
+        MushiProgramError::NotEnoughAdminSigners
+    );
+
+    let previous_admins = main_state.admins.to_vec();
+    main_state.admins = main_state.pending_admins;
+    main_state.admin_threshold = main_state.pending_admin_threshold;
+    main_state.pending_admins = [Pubkey::default(); MAX_ADMINS];
+    main_state.pending_admin_threshold = 0;
+
+    emit!(AdminTransferred {
+        previous_admins,
+        admins: main_state.admins.to_vec(),
+        threshold: main_state.admin_threshold,
+    });
+    Ok(())
+}
+
+pub fn cancel_admin_transfer(ctx: Context<AAdminTransfer>) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let main_state = &mut ctx.accounts.main_state;
+    require!(main_state.pending_admin_threshold != 0, MushiProgramError::NoPendingAdmin);
+    let pending_admins = main_state.pending_admins.to_vec();
+    main_state.pending_admins = [Pubkey::default(); MAX_ADMINS];
+    main_state.pending_admin_threshold = 0;
+
+    emit!(AdminTransferCancelled { pending_admins });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AAdminTransfer<'info> {
+    #[account(constraint = main_state.is_admin(admin.key) @MushiProgramError::UnAuthorised)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+}
+
+#[derive(Accounts)]
+pub struct AAcceptAdmins<'info> {
+    #[account(
+        constraint = main_state.pending_admin_threshold != 0 @MushiProgramError::NoPendingAdmin,
+        constraint = main_state.is_pending_admin(pending_admin.key) @MushiProgramError::UnAuthorised
+    )]
+    pub pending_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+}
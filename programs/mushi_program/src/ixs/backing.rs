@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::VAULT_SEED,
+    error::MushiProgramError,
+    events::{BackingDonated, BackingSynced},
+    state::{GlobalStats, MainState},
+    utils::transfer_sol,
+};
+
+/// Adds SOL to the backing without minting tokens, raising the price for all holders
+pub fn donate_to_backing(ctx: Context<ADonateToBacking>, sol_amount: u64) -> Result<()> {
+    require!(ctx.accounts.global_state.started, MushiProgramError::NotStarted);
+    require!(sol_amount > 0, MushiProgramError::InvalidSolAmount);
+
+    transfer_sol(
+        ctx.accounts.donor.to_account_info(),
+        ctx.accounts.token_vault_owner.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        sol_amount,
+        None,
+    )?;
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.backing = global_state.backing.checked_add(sol_amount).unwrap();
+
+    emit!(BackingDonated {
+        donor: ctx.accounts.donor.key(),
+        sol_amount,
+    });
+    Ok(())
+}
+
+/// Moves lamports sent straight to the vault, outside of any instruction, into the backing
+pub fn sync_backing(ctx: Context<ASyncBacking>) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let global_state = &mut ctx.accounts.global_state;
+    require!(global_state.started, MushiProgramError::NotStarted);
+
+    let accounted = Rent::get()?
+        .minimum_balance(0)
+        .checked_add(global_state.backing)
+        .unwrap()
+        .checked_add(global_state.accrued_fees)
+        .unwrap()
+        .checked_add(global_state.referral_rewards)
+        .unwrap();
+    let stray = ctx.accounts.token_vault_owner.lamports().saturating_sub(accounted);
+    require!(stray > 0, MushiProgramError::NothingToSync);
+    global_state.backing = global_state.backing.checked_add(stray).unwrap();
+
+    emit!(BackingSynced { sol_amount: stray });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ADonateToBacking<'info> {
+    #[account(mut)]
+    pub donor: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED],
+        bump,
+    )]
+    pub token_vault_owner: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ASyncBacking<'info> {
+    #[account(
+        constraint = main_state.is_admin(admin.key) @MushiProgramError::UnAuthorised
+    )]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [VAULT_SEED],
+        bump,
+    )]
+    pub token_vault_owner: SystemAccount<'info>,
+}
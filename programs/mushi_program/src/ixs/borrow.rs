@@ -3,7 +3,7 @@ use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
     constants::{
-        FEES_BUY, FEES_SELL, FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
+        FEE_BASE_1000, MIN, PAUSE_NEW_LOANS, SECONDS_IN_A_DAY, VAULT_SEED
     }, context::{ACommonExtLoan, ACommonExtSubLoan}, error::MushiProgramError, utils::{
         add_loans_by_date, burn_tokens, get_interest_fee, get_midnight_timestamp, liquidate, mint_to_tokens_by_main_state, transfer_sol, transfer_tokens
     }
@@ -11,8 +11,11 @@ use crate::{
 use crate::context::common::ACommon;
 
 pub fn borrow(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64)->Result<()>{
+    ctx.accounts.common.main_state.require_not_paused(PAUSE_NEW_LOANS)?;
     let is_expired = ctx.accounts.common.is_loan_expired()?;
     let user_mushi = ctx.accounts.common.sol_to_mushi_no_trade_ceil(sol_amount)?;
+    let sol_fee = ctx.accounts.common.interest_fee(sol_amount, number_of_days)?;
+    let fee_address_fee = ctx.accounts.common.protocol_fee(sol_fee);
     let user_loan = &mut ctx.accounts.common.user_loan;
     
     require!(number_of_days < 366, MushiProgramError::InvalidNumberOfDays);
@@ -29,6 +32,7 @@ pub fn borrow(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64)-
 
     let global_state = &mut ctx.accounts.common.global_state;
     liquidate(
+        &ctx.accounts.common.main_state,
         &mut ctx.accounts.common.last_liquidation_date_state,
         global_state,
         ctx.accounts.common.token_vault.to_account_info(),
@@ -40,8 +44,6 @@ pub fn borrow(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64)-
 
     let current_timestamp = Clock::get()?.unix_timestamp;
     let end_date = get_midnight_timestamp(current_timestamp + number_of_days as i64 * SECONDS_IN_A_DAY);
-    let sol_fee = get_interest_fee(sol_amount, number_of_days);
-    let fee_address_fee = sol_fee.checked_mul(3).unwrap().checked_div(10).unwrap();
     // AUDIT: eggs required from user round up?
     let new_user_borrow = sol_amount.checked_mul(99).unwrap().checked_div(100).unwrap();
     
@@ -59,8 +61,6 @@ pub fn borrow(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64)-
         None,
     )?;
     
-    require!(fee_address_fee > MIN, MushiProgramError::InvalidFeeAmount);
-
     let signer_seeds:&[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
 
     transfer_sol(
@@ -69,21 +69,20 @@ pub fn borrow(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64)-
         ctx.accounts.system_program.to_account_info(), 
         new_user_borrow - sol_fee, 
         Some(signer_seeds))?;
-    transfer_sol(
-    ctx.accounts.common.token_vault_owner.to_account_info(), 
-    ctx.accounts.common.fee_receiver.to_account_info(), 
-    ctx.accounts.common.system_program.to_account_info(), 
-    fee_address_fee, 
-    Some(signer_seeds))?;
+    ctx.accounts.common.sub_backing(new_user_borrow - sol_fee + fee_address_fee);
+    let referral_share = ctx.accounts.common.main_state.loan_referral_share;
+    ctx.accounts.common.accrue_loan_fee(fee_address_fee, sol_amount, referral_share)?;
 
     // ctx.accounts.add_loans_by_date( new_user_borrow, user_mushi)?;
     add_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_end_date, new_user_borrow, user_mushi)?;
 
+    ctx.accounts.common.announce_promotion()?;
     ctx.accounts.common.safety_check()?;
     Ok(())
 }
 
 pub fn borrow_more(ctx:Context<ACommonExtSubLoan>, sol_amount:u64)->Result<()>{
+    ctx.accounts.common.main_state.require_not_paused(PAUSE_NEW_LOANS)?;
     let is_expired = ctx.accounts.common.is_loan_expired()?;
     require!(!is_expired, MushiProgramError::LoanExpired);
     require!(sol_amount != 0, MushiProgramError::InvalidSolAmount);
@@ -93,6 +92,7 @@ pub fn borrow_more(ctx:Context<ACommonExtSubLoan>, sol_amount:u64)->Result<()>{
  
     let global_state = &mut ctx.accounts.common.global_state;
     liquidate(
+        &ctx.accounts.common.main_state,
         &mut ctx.accounts.common.last_liquidation_date_state,
         global_state,
         ctx.accounts.common.token_vault.to_account_info(),
@@ -107,9 +107,9 @@ pub fn borrow_more(ctx:Context<ACommonExtSubLoan>, sol_amount:u64)->Result<()>{
 
     let today_midnight = get_midnight_timestamp(Clock::get()?.unix_timestamp);
     let new_borrow_length = (user_end_date - today_midnight) / SECONDS_IN_A_DAY;
-    let sol_fee = get_interest_fee(sol_amount, new_borrow_length as u64);
+    let sol_fee = ctx.accounts.common.interest_fee(sol_amount, new_borrow_length as u64)?;
 
-    let fee_address_fee = sol_fee.checked_mul(3).unwrap().checked_div(10).unwrap();
+    let fee_address_fee = ctx.accounts.common.protocol_fee(sol_fee);
     let new_user_borrow = sol_amount.checked_mul(99).unwrap().checked_div(100).unwrap();
     let user_borrowed_in_mushi = ctx.accounts.common.sol_to_mushi_no_trade(user_borrowed)?;
     let user_excess_in_mushi = user_collateral.checked_mul(99).unwrap().checked_div(100).unwrap().checked_sub(user_borrowed_in_mushi).unwrap();
@@ -141,8 +141,6 @@ pub fn borrow_more(ctx:Context<ACommonExtSubLoan>, sol_amount:u64)->Result<()>{
         )?;
     }
     
-    require!(fee_address_fee > MIN, MushiProgramError::InvalidFeeAmount);
-
     let signer_seeds:&[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
     transfer_sol(
         ctx.accounts.common.token_vault_owner.to_account_info(), 
@@ -150,13 +148,11 @@ pub fn borrow_more(ctx:Context<ACommonExtSubLoan>, sol_amount:u64)->Result<()>{
         ctx.accounts.common.system_program.to_account_info(), 
         new_user_borrow - sol_fee, 
         Some(signer_seeds))?;
-    transfer_sol(
-    ctx.accounts.common.token_vault_owner.to_account_info(), 
-    ctx.accounts.common.fee_receiver.to_account_info(), 
-    ctx.accounts.common.system_program.to_account_info(), 
-    fee_address_fee, 
-    Some(signer_seeds))?;
+    ctx.accounts.common.sub_backing(new_user_borrow - sol_fee + fee_address_fee);
+    let referral_share = ctx.accounts.common.main_state.loan_referral_share;
+    ctx.accounts.common.accrue_loan_fee(fee_address_fee, sol_amount, referral_share)?;
     add_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_old_end_date, new_user_borrow, require_collateral_from_user)?;
+    ctx.accounts.common.announce_promotion()?;
     ctx.accounts.common.safety_check()?;
     Ok(())
 }
\ No newline at end of file
@@ -2,139 +2,130 @@ use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
-    constants::{FEES_BUY, FEES_BUY_REFERRAL, FEES_SELL, FEE_BASE_1000, MIN, VAULT_SEED}, context::ACommonExtReferral, error::MushiProgramError, utils::{burn_tokens, liquidate, mint_to_tokens_by_main_state, transfer_sol, trasnfer_sol_to_pubkey} 
+    constants::{FEE_BASE_1000, MIN, PAUSE_BUY, PAUSE_SELL, VAULT_SEED}, error::MushiProgramError, utils::{burn_tokens, get_bps_fee, liquidate, mint_to_tokens_by_main_state, transfer_sol, trasnfer_sol_to_pubkey} 
 };
-use crate::context::common::ACommon;
+use crate::context::common::{ACommon, ACommonExtReferral};
+use std::collections::BTreeMap;
 
-pub fn buy(ctx:Context<ACommon>, sol_amount:u64) -> Result<()> {
-    let mushi = ctx.accounts.sol_to_mushi(sol_amount)?;
-    let global_state =&mut ctx.accounts.global_state;
+pub fn buy(ctx:Context<ACommon>, sol_amount:u64, integrator_fee_bps: u64) -> Result<()> {
+    ctx.accounts.require_trading_started()?;
+    ctx.accounts.check_anti_bot_limit(sol_amount)?;
+    buy_tokens(ctx.accounts, &ctx.bumps, sol_amount, integrator_fee_bps)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PresaleBuyInput {
+    pub sol_amount: u64,
+    /// The user's cap in lamports, as hashed in its allowlist leaf
+    pub max_buy: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Buys before trading opens, for the wallets of the presale allowlist
+pub fn presale_buy(ctx:Context<ACommon>, input: PresaleBuyInput) -> Result<()> {
+    ctx.accounts.check_presale_buy(input.sol_amount, input.max_buy, &input.proof)?;
+    buy_tokens(ctx.accounts, &ctx.bumps, input.sol_amount, 0)
+}
+
+fn buy_tokens(accounts: &mut ACommon, bumps: &BTreeMap<String, u8>, sol_amount: u64, integrator_fee_bps: u64) -> Result<()> {
+    accounts.main_state.require_not_paused(PAUSE_BUY)?;
+    // the integrator fee comes off the amount spent on the buy
+    let integrator_fee = accounts.integrator_fee(sol_amount, integrator_fee_bps)?;
+    accounts.pay_integrator_fee(integrator_fee, None)?;
+    let sol_amount = sol_amount.checked_sub(integrator_fee).unwrap();
+
+    let fee_schedule = accounts.main_state.fee_schedule;
+    let referred = accounts.has_bound_referrer()?;
+    // without a referrer the referral part goes to the treasury as well
+    let (fee_treasury, fee_referral) = if referred {
+        (
+            accounts.discounted_treasury_fee(get_bps_fee(sol_amount, accounts.buy_treasury_bps()?))?,
+            get_bps_fee(sol_amount, fee_schedule.buy_referral_bps),
+        )
+    } else {
+        (
+            accounts.discounted_treasury_fee(
+                get_bps_fee(sol_amount, accounts.buy_treasury_bps()? + fee_schedule.buy_referral_bps)
+            )?,
+            0,
+        )
+    };
+    let left_sol_amount = sol_amount.checked_sub(fee_treasury + fee_referral).unwrap();
+
+    let mushi = accounts.sol_to_mushi(left_sol_amount)?;
+    let mushi_after_fee = mushi - get_bps_fee(mushi, fee_schedule.buy_backing_bps);
+    let global_state =&mut accounts.global_state;
     liquidate(
-        &mut ctx.accounts.last_liquidation_date_state,
+        &accounts.main_state,
+        &mut accounts.last_liquidation_date_state,
         global_state,
-        ctx.accounts.token_vault.to_account_info(),
-        ctx.accounts.token.to_account_info(),
-        ctx.accounts.token_vault_owner.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        *ctx.bumps.get("token_vault_owner").unwrap(),
+        accounts.token_vault.to_account_info(),
+        accounts.token.to_account_info(),
+        accounts.token_vault_owner.to_account_info(),
+        accounts.token_program.to_account_info(),
+        *bumps.get("token_vault_owner").unwrap(),
     )?;
     let is_started = global_state.started;
     require!(is_started, MushiProgramError::NotStarted);
     // minting tokens
     mint_to_tokens_by_main_state(
-        ctx.accounts.token.to_account_info(), 
-    ctx.accounts.main_state.to_account_info(), 
-        ctx.accounts.user_ata.to_account_info(), 
-        ctx.accounts.token_program.to_account_info(), 
-        mushi * ctx.accounts.main_state.buy_fee / FEE_BASE_1000, 
-        *ctx.bumps.get("main_state").unwrap()
+        accounts.token.to_account_info(), 
+    accounts.main_state.to_account_info(), 
+        accounts.user_ata.to_account_info(), 
+        accounts.token_program.to_account_info(), 
+        mushi_after_fee, 
+        *bumps.get("main_state").unwrap()
     )?;
-    global_state.token_supply += mushi * ctx.accounts.main_state.buy_fee / FEE_BASE_1000;
+    global_state.token_supply += mushi_after_fee;
     
     // calc sender SOLs
-    
-    let fee: u64 = sol_amount.checked_mul(FEES_BUY+FEES_BUY_REFERRAL).unwrap().checked_div(10_000).unwrap();
-    // let fee: u64 = sol_amount.checked_div(FEES_BUY).unwrap();
-    
-    require!(fee > MIN, MushiProgramError::TooSmallTeamFee);
-    let left_sol_amount = sol_amount.checked_sub(fee).unwrap();
     transfer_sol(
-        ctx.accounts.user.to_account_info(), 
-    ctx.accounts.fee_receiver.to_account_info(), 
-    ctx.accounts.system_program.to_account_info(), 
-    fee, 
-    None)?;
-    transfer_sol(
-        ctx.accounts.user.to_account_info(), 
-        ctx.accounts.token_vault_owner.to_account_info(), 
-        ctx.accounts.system_program.to_account_info(), 
-        left_sol_amount, 
+        accounts.user.to_account_info(), 
+        accounts.token_vault_owner.to_account_info(), 
+        accounts.system_program.to_account_info(), 
+        sol_amount, 
         None)?;
-    ctx.accounts.safety_check()?;
+    accounts.add_backing(left_sol_amount);
+    accounts.accrue_protocol_fee(fee_treasury);
+    if referred {
+        accounts.credit_referrer(sol_amount, fee_referral)?;
+    }
+    accounts.record_volume(sol_amount)?;
+    accounts.announce_promotion()?;
+    accounts.safety_check()?;
     Ok(())
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub struct BuyWithReferralInput {
     pub sol_amount: u64,
-    pub referral_pubkey: Pubkey,
+    /// Ignored when a referral code account is passed
+    pub referral_pubkey: Option<Pubkey>,
+    pub integrator_fee_bps: u64,
 }
 
+/// Binds the user to the referrer on their first referred buy, then buys as usual.
+/// The referrer is designated either by its pubkey or by one of its referral codes.
 pub fn buy_with_referral(ctx:Context<ACommonExtReferral>,  input: BuyWithReferralInput ) -> Result<()> {
-    let sol_amount = input.sol_amount;
-    let referral = &mut ctx.accounts.referral;
-    
-
-    let mushi = ctx.accounts.common.sol_to_mushi(sol_amount)?;
-    let global_state =&mut ctx.accounts.common.global_state;
-    liquidate(
-        &mut ctx.accounts.common.last_liquidation_date_state,
-        global_state,
-        ctx.accounts.common.token_vault.to_account_info(),
-        ctx.accounts.common.token.to_account_info(),
-        ctx.accounts.common.token_vault_owner.to_account_info(),
-        ctx.accounts.common.token_program.to_account_info(),
-        *ctx.bumps.get("token_vault_owner").unwrap(),
-    )?;
-    let is_started = global_state.started;
-    require!(is_started, MushiProgramError::NotStarted);
-
-    require!(ctx.accounts.referral.is_some(), MushiProgramError::ReferralNotFound);
-    let referral_account = ctx.accounts.referral.as_ref().unwrap();
-    require!(referral_account.key() == input.referral_pubkey, MushiProgramError::InvalidReferralAccount);
-
-    // minting tokens
-    mint_to_tokens_by_main_state(
-        ctx.accounts.common.token.to_account_info(), 
-    ctx.accounts.common.main_state.to_account_info(), 
-        ctx.accounts.common.user_ata.to_account_info(), 
-        ctx.accounts.common.token_program.to_account_info(), 
-        mushi * ctx.accounts.common.main_state.buy_fee / FEE_BASE_1000, 
-        *ctx.bumps.get("main_state").unwrap()
-    )?;
-    global_state.token_supply += mushi * ctx.accounts.common.main_state.buy_fee / FEE_BASE_1000;
-    
-    // calc sender SOLs
-    
-    let fee_treasury: u64 = sol_amount.checked_mul(FEES_BUY).unwrap().checked_div(10_000).unwrap();
-    let fee_referral: u64 = sol_amount.checked_mul(FEES_BUY_REFERRAL).unwrap().checked_div(10_000).unwrap();
-    // let fee: u64 = sol_amount.checked_div(FEES_BUY).unwrap();
-    
-    require!(fee_treasury > MIN, MushiProgramError::TooSmallTeamFee);
-    if fee_referral <= MIN {
-        return Err(MushiProgramError::TooSmallTeamFee.into());
-    }
-
-    let left_sol_amount = sol_amount.checked_sub(fee_treasury+fee_referral).unwrap();
-    transfer_sol(
-        ctx.accounts.common.user.to_account_info(), 
-    ctx.accounts.common.fee_receiver.to_account_info(), 
-    ctx.accounts.common.system_program.to_account_info(), 
-    fee_treasury, 
-    None)?;
-    
-    transfer_sol(
-        ctx.accounts.common.user.to_account_info(), 
-        ctx.accounts.referral.as_ref().unwrap().to_account_info(), 
-        ctx.accounts.common.system_program.to_account_info(), 
-        fee_referral, 
-        None
-    )?;
-    
-    transfer_sol(
-        ctx.accounts.common.user.to_account_info(), 
-        ctx.accounts.common.token_vault_owner.to_account_info(), 
-        ctx.accounts.common.system_program.to_account_info(), 
-        left_sol_amount, 
-        None)?;
-    ctx.accounts.common.safety_check()?;
-    Ok(())
+    let common = &mut ctx.accounts.common;
+    require!(common.referrer.is_some(), MushiProgramError::ReferralNotFound);
+    let referral_pubkey = match &ctx.accounts.referral_code {
+        Some(referral_code) => referral_code.referrer,
+        None => input.referral_pubkey.ok_or(MushiProgramError::ReferralNotFound)?,
+    };
+    require!(common.referrer.as_ref().unwrap().authority == referral_pubkey, MushiProgramError::InvalidReferralAccount);
+    common.bind_referrer()?;
+    common.require_trading_started()?;
+    common.check_anti_bot_limit(input.sol_amount)?;
+    buy_tokens(common, &ctx.bumps, input.sol_amount, input.integrator_fee_bps)
 }
 
-pub fn sell(ctx:Context<ACommon>, token_amount:u64)->Result<()>{
+pub fn sell(ctx:Context<ACommon>, token_amount:u64, integrator_fee_bps: u64)->Result<()>{
+    ctx.accounts.main_state.require_not_paused(PAUSE_SELL)?;
     let sol_amount = ctx.accounts.mushi_to_sol(token_amount)?;
     let global_state =&mut ctx.accounts.global_state;
     liquidate(
+        &ctx.accounts.main_state,
         &mut ctx.accounts.last_liquidation_date_state,
         global_state,
         ctx.accounts.token_vault.to_account_info(),
@@ -159,25 +150,44 @@ pub fn sell(ctx:Context<ACommon>, token_amount:u64)->Result<()>{
     let vault_owner = ctx.accounts.token_vault_owner.to_account_info();
     let signer_seeds:&[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
     
-    let sol_fee_amount = sol_amount.checked_mul(FEES_SELL).unwrap().checked_div(10_000).unwrap();
+    let fee_schedule = ctx.accounts.main_state.fee_schedule;
+    let referred = ctx.accounts.has_bound_referrer()?;
+    // without a referrer the referral part goes to the treasury as well
+    let (sol_fee_amount, referral_fee_amount) = if referred {
+        (
+            ctx.accounts.discounted_treasury_fee(get_bps_fee(sol_amount, ctx.accounts.sell_treasury_bps()?))?,
+            get_bps_fee(sol_amount, fee_schedule.sell_referral_bps),
+        )
+    } else {
+        (
+            ctx.accounts.discounted_treasury_fee(
+                get_bps_fee(sol_amount, ctx.accounts.sell_treasury_bps()? + fee_schedule.sell_referral_bps)
+            )?,
+            0,
+        )
+    };
+    let backing_fee_amount = get_bps_fee(sol_amount, fee_schedule.sell_backing_bps);
+    let integrator_fee = ctx.accounts.integrator_fee(sol_amount, integrator_fee_bps)?;
 
-    require!(sol_fee_amount > MIN, MushiProgramError::TooSmallInputAmount);
+    require!(sol_amount > 0, MushiProgramError::TooSmallInputAmount);
 
     // Payment to seller
     transfer_sol(
         vault_owner.to_account_info(), 
         seller.to_account_info(), 
         system_program.to_account_info(), 
-        sol_amount * ctx.accounts.main_state.sell_fee / FEE_BASE_1000, 
+        sol_amount - sol_fee_amount - referral_fee_amount - backing_fee_amount - integrator_fee, 
         Some(signer_seeds))?;
+    ctx.accounts.pay_integrator_fee(integrator_fee, Some(signer_seeds))?;
+    // the backing fee stays in the backing
+    ctx.accounts.sub_backing(sol_amount - backing_fee_amount);
     // team fee
-
-    transfer_sol(
-        vault_owner.to_account_info(), 
-        ctx.accounts.fee_receiver.to_account_info(), 
-        system_program.to_account_info(), 
-        sol_fee_amount, 
-        Some(signer_seeds))?;
+    ctx.accounts.accrue_protocol_fee(sol_fee_amount);
+    if referred {
+        ctx.accounts.credit_referrer(sol_amount, referral_fee_amount)?;
+    }
+    ctx.accounts.record_volume(sol_amount)?;
+    ctx.accounts.announce_promotion()?;
     ctx.accounts.safety_check()?;
     Ok(())
 }
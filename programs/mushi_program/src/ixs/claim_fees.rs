@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FEE_BASE_10000, VAULT_SEED},
+    error::MushiProgramError,
+    state::{GlobalStats, MainState, Role, Roles},
+    utils::transfer_sol,
+};
+
+/// Pays the protocol fees accrued in the vault to the fee recipients, split by weight.
+/// The recipient accounts are passed as remaining accounts, in the configured order.
+pub fn claim_fees<'info>(ctx: Context<'_, '_, '_, 'info, AClaimFees<'info>>) -> Result<()> {
+    let main_state = &ctx.accounts.main_state;
+    let claimer = ctx.accounts.claimer.key();
+    require!(
+        main_state.is_admin(&claimer)
+            || main_state.is_fee_recipient(&claimer)
+            || ctx.accounts.roles.has_role(Role::TreasuryClaimer, &claimer),
+        MushiProgramError::UnAuthorised
+    );
+
+    let amount = ctx.accounts.global_state.accrued_fees;
+    require!(amount > 0, MushiProgramError::NothingToClaim);
+
+    let recipients: Vec<_> = main_state.active_fee_recipients().copied().collect();
+    require!(
+        ctx.remaining_accounts.len() == recipients.len(),
+        MushiProgramError::InvalidFeeRecipientAccount
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
+    let mut remaining = amount;
+    for (i, (recipient, account)) in recipients.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+        require!(
+            account.key() == recipient.address,
+            MushiProgramError::InvalidFeeRecipientAccount
+        );
+        // the last recipient also gets the rounding dust
+        let share = if i == recipients.len() - 1 {
+            remaining
+        } else {
+            ((amount as u128 * recipient.weight_bps as u128) / FEE_BASE_10000 as u128) as u64
+        };
+        remaining -= share;
+        if share == 0 {
+            continue;
+        }
+        transfer_sol(
+            ctx.accounts.token_vault_owner.to_account_info(),
+            account.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            share,
+            Some(signer_seeds),
+        )?;
+    }
+    ctx.accounts.global_state.accrued_fees = 0;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AClaimFees<'info> {
+    pub claimer: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED],
+        bump,
+    )]
+    pub token_vault_owner: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
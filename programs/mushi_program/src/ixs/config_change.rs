@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::MushiProgramError,
+    events::{ConfigChangeCancelled, ConfigChangeExecuted, ConfigChangeProposed},
+    ixs::{apply_main_state_update, UpdateMainStateInput},
+    state::{MainState, PendingConfigChange, Roles},
+};
+
+/// Queues a configuration change, executable once the configured timelock has elapsed.
+/// Only one change can be pending at a time.
+pub fn propose_config_change(ctx: Context<AProposeConfigChange>, input: UpdateMainStateInput) -> Result<()> {
+    input.check_roles(
+        &ctx.accounts.main_state,
+        &ctx.accounts.roles,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    // reject invalid changes now rather than at execution
+    let mut preview = (*ctx.accounts.main_state).clone();
+    apply_main_state_update(&mut preview, input.clone())?;
+
+    let eta = Clock::get()?.unix_timestamp + ctx.accounts.main_state.config_timelock as i64;
+    let pending_config_change = &mut ctx.accounts.pending_config_change;
+    pending_config_change.proposer = ctx.accounts.authority.key();
+    pending_config_change.eta = eta;
+    pending_config_change.change = input;
+
+    emit!(ConfigChangeProposed { eta });
+    Ok(())
+}
+
+pub fn execute_config_change(ctx: Context<AExecuteConfigChange>) -> Result<()> {
+    let pending_config_change = &ctx.accounts.pending_config_change;
+    require!(
+        Clock::get()?.unix_timestamp >= pending_config_change.eta,
+        MushiProgramError::ConfigChangeNotReady
+    );
+    apply_main_state_update(&mut ctx.accounts.main_state, pending_config_change.change.clone())?;
+
+    emit!(ConfigChangeExecuted { eta: pending_config_change.eta });
+    Ok(())
+}
+
+/// Needs the same roles as proposing the change
+pub fn cancel_config_change(ctx: Context<ACancelConfigChange>) -> Result<()> {
+    ctx.accounts.pending_config_change.change.check_roles(
+        &ctx.accounts.main_state,
+        &ctx.accounts.roles,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    emit!(ConfigChangeCancelled { eta: ctx.accounts.pending_config_change.eta });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AProposeConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PendingConfigChange::PREFIX_SEED],
+        bump,
+        space = 8 + PendingConfigChange::MAX_SIZE,
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer,
+        seeds = [PendingConfigChange::PREFIX_SEED],
+        bump,
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+    /// CHECK: checked against the pending change
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ACancelConfigChange<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer,
+        seeds = [PendingConfigChange::PREFIX_SEED],
+        bump,
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+    /// CHECK: checked against the pending change
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
@@ -3,7 +3,7 @@ use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
     constants::{
-        FEES_BUY, FEES_SELL, FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
+        FEE_BASE_1000, MIN, PAUSE_LOAN_MODIFICATIONS, SECONDS_IN_A_DAY, VAULT_SEED
     }, context::{ACommonExtLoan, ACommonExtExtendLoan}, error::MushiProgramError, utils::{
         add_loans_by_date, burn_tokens, get_interest_fee, get_midnight_timestamp, liquidate, mint_to_tokens_by_main_state, sub_loans_by_date, transfer_sol, transfer_tokens
     }
@@ -11,6 +11,7 @@ use crate::{
 use crate::context::common::ACommon;
 
 pub fn extend_loan(ctx:Context<ACommonExtExtendLoan>, number_of_days: u64 )->Result<()>{
+    ctx.accounts.common.main_state.require_not_paused(PAUSE_LOAN_MODIFICATIONS)?;
     let user_loan = & ctx.accounts.common.user_loan;
     let old_end_date = user_loan.end_date;
     let _number_of_days = user_loan.number_of_days;
@@ -18,12 +19,11 @@ pub fn extend_loan(ctx:Context<ACommonExtExtendLoan>, number_of_days: u64 )->Res
     let collateral = user_loan.collateral;
 
     let new_end_date = old_end_date + number_of_days as i64 * SECONDS_IN_A_DAY;
-    let loan_fee = get_interest_fee(borrowed, number_of_days);
+    let loan_fee = ctx.accounts.common.interest_fee(borrowed, number_of_days)?;
     
     require!(!ctx.accounts.common.is_loan_expired()?, MushiProgramError::LoanExpired);
 
-    let fee_address_fee = loan_fee.checked_mul(3).unwrap().checked_div(10).unwrap();
-    require!(fee_address_fee > MIN, MushiProgramError::InvalidFeeAmount);
+    let fee_address_fee = ctx.accounts.common.protocol_fee(loan_fee);
 
     transfer_sol(
         ctx.accounts.common.user.to_account_info(), 
@@ -32,13 +32,9 @@ pub fn extend_loan(ctx:Context<ACommonExtExtendLoan>, number_of_days: u64 )->Res
         loan_fee, 
         None)?;
 
-    let signer_seeds:&[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
-    transfer_sol(
-        ctx.accounts.common.token_vault_owner.to_account_info(), 
-        ctx.accounts.common.fee_receiver.to_account_info(), 
-        ctx.accounts.common.system_program.to_account_info(), 
-        fee_address_fee, 
-        Some(signer_seeds))?;
+    ctx.accounts.common.add_backing(loan_fee - fee_address_fee);
+    let referral_share = ctx.accounts.common.main_state.loan_referral_share;
+    ctx.accounts.common.accrue_loan_fee(fee_address_fee, borrowed, referral_share)?;
     sub_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_old_end_date, borrowed, collateral)?;
     add_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_new_end_date, borrowed, collateral)?;
     let user_loan = &mut ctx.accounts.common.user_loan;
@@ -47,6 +43,7 @@ pub fn extend_loan(ctx:Context<ACommonExtExtendLoan>, number_of_days: u64 )->Res
 
     let current_timestamp = Clock::get()?.unix_timestamp;
     require!((new_end_date - current_timestamp) / SECONDS_IN_A_DAY < 366, MushiProgramError::InvalidNumberOfDays);
+    ctx.accounts.common.announce_promotion()?;
     ctx.accounts.common.safety_check()?;
     
     Ok(())
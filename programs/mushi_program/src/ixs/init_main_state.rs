@@ -1,26 +1,34 @@
 use anchor_lang::prelude::*;
-use crate::state::{MainState, GlobalStats, DailyStats};
+use crate::state::{MainState, GlobalStats, Roles, DailyStats, FeeChangeLimits, FeeRecipient, FeeSchedule};
 use crate::utils::get_midnight_timestamp;
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub struct InitializeInput {
-    pub fee_receiver: Pubkey,
-    pub sell_fee: u64,
-    pub buy_fee: u64,
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub fee_schedule: FeeSchedule,
     pub buy_fee_leverage: u64,
+    pub protocol_fee_share: u64,
+    pub flash_close_fee: u64,
 }
 
 pub fn init_main_state(ctx: Context<AInitializeState>, input: InitializeInput) -> Result<()> {
     // main state
     let main_state = &mut ctx.accounts.main_state;
-    main_state.admin = ctx.accounts.admin.key();
-    main_state.fee_receiver = input.fee_receiver.key();
-    main_state.sell_fee = input.sell_fee;
-    main_state.buy_fee = input.buy_fee;
+    // single admin until an admin set is proposed and accepted
+    main_state.admins[0] = ctx.accounts.admin.key();
+    main_state.admin_threshold = 1;
+    main_state.set_fee_recipients(&input.fee_recipients)?;
+    main_state.fee_schedule = input.fee_schedule;
     main_state.buy_fee_leverage = input.buy_fee_leverage;
+    main_state.protocol_fee_share = input.protocol_fee_share;
+    main_state.flash_close_fee = input.flash_close_fee;
+    main_state.fee_change_limits = FeeChangeLimits::DEFAULT;
+    main_state.validate_config()?;
+    main_state.version = MainState::VERSION;
 
     // global state
     let global_state = &mut ctx.accounts.global_state;
     global_state.last_liquidation_date = get_midnight_timestamp(Clock::get().unwrap().unix_timestamp);
+    global_state.version = GlobalStats::VERSION;
     Ok(())
 }
 
@@ -44,5 +52,13 @@ pub struct AInitializeState<'info> {
         space =  8 + MainState::MAX_SIZE,
     )]
     pub main_state: Box<Account<'info, MainState>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+        space =  8 + Roles::MAX_SIZE,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
@@ -3,16 +3,20 @@ use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
     constants::{
-        FEES_BUY, FEES_SELL, FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
+        FEE_BASE_1000, MIN, PAUSE_NEW_LOANS, SECONDS_IN_A_DAY, VAULT_SEED
     }, context::ACommonExtLoan, error::MushiProgramError, utils::{
         add_loans_by_date, burn_tokens, get_interest_fee, get_midnight_timestamp, liquidate, mint_to_tokens_by_main_state, transfer_sol, transfer_tokens
     }
 };
 use crate::context::common::ACommon;
 
-pub fn leverage(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64)->Result<()>{
+pub fn leverage(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64, integrator_fee_bps: u64)->Result<()>{
+    ctx.accounts.common.main_state.require_not_paused(PAUSE_NEW_LOANS)?;
     let is_started = ctx.accounts.common.global_state.started;
     require!(is_started, MushiProgramError::NotStarted);
+    ctx.accounts.common.require_trading_started()?;
+    // leverage buys would get around the per wallet limit
+    require!(!ctx.accounts.common.in_anti_bot_window()?, MushiProgramError::AntiBotLimitExceeded);
     require!(number_of_days < 366, MushiProgramError::InvalidNumberOfDays);
     require!(sol_amount != 0, MushiProgramError::InvalidSolAmount);
     
@@ -36,6 +40,7 @@ pub fn leverage(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64
     
     // Liquidate
     liquidate(
+        &ctx.accounts.common.main_state,
         &mut ctx.accounts.common.last_liquidation_date_state,
         &mut ctx.accounts.common.global_state,
         ctx.accounts.common.token_vault.to_account_info(),
@@ -49,11 +54,14 @@ pub fn leverage(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64
     let end_date = get_midnight_timestamp(current_timestamp + number_of_days as i64 * SECONDS_IN_A_DAY);
     let user_sol = sol_amount.checked_sub(sol_fee).unwrap();
 
-    let fee_address_amount = sol_fee.checked_mul(3).unwrap().checked_div(10).unwrap();
+    let protocol_fee = ctx.accounts.common.protocol_fee(sol_fee);
+    // the volume tier discount comes off the protocol share and is not charged to the user
+    let fee_address_amount = ctx.accounts.common.discounted_treasury_fee(protocol_fee)?;
+    let fee_discount = protocol_fee - fee_address_amount;
     let user_borrow = user_sol.checked_mul(99).unwrap().checked_div(100).unwrap();
     let over_collateralization_amount = user_sol/100;
     let sub_value = fee_address_amount.checked_add(over_collateralization_amount).unwrap();
-    let total_fee = sol_fee.checked_add(over_collateralization_amount).unwrap();
+    let total_fee = sol_fee.checked_add(over_collateralization_amount).unwrap() - fee_discount;
     let mut fee_overage = 0;
 
     let signer_seeds:&[&[&[u8]]] = &[&[VAULT_SEED, &[bump]]];
@@ -71,23 +79,18 @@ pub fn leverage(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64
         main_state_bump,
     )?;
 
-    require!(fee_address_amount > MIN, MushiProgramError::InvalidFeeAmount);
-    
     transfer_sol(
         ctx.accounts.common.user.to_account_info(), 
         ctx.accounts.common.token_vault_owner.to_account_info(), 
         ctx.accounts.common.system_program.to_account_info(), 
         total_fee, 
         None)?;
-
-    // Transfer SOL
-    transfer_sol(
-        ctx.accounts.common.token_vault_owner.to_account_info(),
-        ctx.accounts.common.fee_receiver.to_account_info(),
-        ctx.accounts.common.system_program.to_account_info(),
-        fee_address_amount,
-        Some(signer_seeds)
-    )?;
+    ctx.accounts.common.add_backing(total_fee - fee_address_amount);
+    // the integrator fee is paid by the user on top of the leverage fee
+    let integrator_fee = ctx.accounts.common.integrator_fee(sol_amount, integrator_fee_bps)?;
+    ctx.accounts.common.pay_integrator_fee(integrator_fee, None)?;
+    let referral_share = ctx.accounts.common.main_state.leverage_referral_share;
+    ctx.accounts.common.accrue_loan_fee(fee_address_amount, sol_amount, referral_share)?;
     
     // Update loans by date
     add_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_end_date, user_borrow, user_mushi)?;
@@ -99,6 +102,8 @@ pub fn leverage(ctx:Context<ACommonExtLoan>, number_of_days: u64, sol_amount:u64
     user_loan.end_date = end_date;
     user_loan.number_of_days = number_of_days;
     
+    ctx.accounts.common.record_volume(sol_amount)?;
+    ctx.accounts.common.announce_promotion()?;
     ctx.accounts.common.safety_check()?;
     
     Ok(())
@@ -0,0 +1,286 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FEE_BASE_1000, MAX_BACKING_FEE_BPS, MIN_BACKING_FEE_BPS, VAULT_SEED},
+    error::MushiProgramError,
+    events::AccountMigrated,
+    state::{DailyStats, FeeChangeLimits, FeeRecipient, FeeSchedule, GlobalStats, LaunchBuyer, MainState, Roles, UserLoan},
+    utils::{get_date_string_from_timestamp, realloc_account},
+};
+
+/// Main state layout before versioning, `version` 0
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyMainState {
+    pub admin: Pubkey,
+    pub fee_receiver: Pubkey,
+    /// Part of the bought tokens minted to the buyer (FEE_BASE_1000)
+    pub buy_fee: u64,
+    /// Part of the sold SOL paid to the seller (FEE_BASE_1000)
+    pub sell_fee: u64,
+    pub buy_fee_leverage: u64,
+}
+
+impl LegacyMainState {
+    // the trade fees that were hardcoded before the fee schedule
+    const BUY_TREASURY_BPS: u64 = 100;
+    const BUY_REFERRAL_BPS: u64 = 25;
+    const SELL_TREASURY_BPS: u64 = 125;
+    const PROTOCOL_FEE_SHARE: u64 = 300;
+    const FLASH_CLOSE_FEE: u64 = 10;
+
+    /// Same trade fees as the legacy fields, backing fees outside the current bounds
+    /// are moved to the nearest valid one
+    fn fee_schedule(&self) -> FeeSchedule {
+        let buy_fee_bps = FEE_BASE_1000.saturating_sub(self.buy_fee) * 10;
+        let sell_fee_bps = FEE_BASE_1000.saturating_sub(self.sell_fee) * 10;
+        FeeSchedule {
+            buy_treasury_bps: Self::BUY_TREASURY_BPS,
+            buy_referral_bps: Self::BUY_REFERRAL_BPS,
+            buy_backing_bps: buy_fee_bps.clamp(MIN_BACKING_FEE_BPS, MAX_BACKING_FEE_BPS),
+            sell_treasury_bps: Self::SELL_TREASURY_BPS,
+            sell_referral_bps: 0,
+            sell_backing_bps: sell_fee_bps
+                .saturating_sub(Self::SELL_TREASURY_BPS)
+                .clamp(MIN_BACKING_FEE_BPS, MAX_BACKING_FEE_BPS),
+        }
+    }
+
+    fn migrate(&self, main_state: &mut MainState) {
+        main_state.admins[0] = self.admin;
+        main_state.admin_threshold = 1;
+        main_state.fee_recipients[0] = FeeRecipient { address: self.fee_receiver, weight_bps: 10_000 };
+        main_state.fee_schedule = self.fee_schedule();
+        main_state.buy_fee_leverage = self.buy_fee_leverage;
+        main_state.protocol_fee_share = Self::PROTOCOL_FEE_SHARE;
+        main_state.flash_close_fee = Self::FLASH_CLOSE_FEE;
+    }
+}
+
+/// Grows the main state to the current layout and fills the fields added since its stored version,
+/// new fields start zeroed. A version 0 account is read with the legacy layout and rewritten.
+/// Has to run before the other migrations.
+pub fn migrate_main_state(ctx: Context<AMigrateMainState>) -> Result<()> {
+    let account = ctx.accounts.main_state.to_account_info();
+    let legacy = if account.data_len() == 8 + LegacyMainState::INIT_SPACE {
+        Some(LegacyMainState::deserialize(&mut &account.try_borrow_data()?[8..])?)
+    } else {
+        None
+    };
+    realloc_account(
+        account.clone(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        8 + MainState::MAX_SIZE,
+    )?;
+    if legacy.is_some() {
+        // keep the discriminator, the legacy fields are mapped below
+        account.try_borrow_mut_data()?[8..].fill(0);
+    }
+    let mut main_state: Account<MainState> = Account::try_from(&account)?;
+    match legacy {
+        Some(legacy) => {
+            legacy.migrate(&mut main_state);
+            main_state.validate_config()?;
+        }
+        // any other unversioned layout is unknown
+        None => require!(main_state.version != 0, MushiProgramError::InvalidAccountVersion),
+    }
+    require!(main_state.is_admin(ctx.accounts.admin.key), MushiProgramError::UnAuthorised);
+    main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    require!(main_state.version <= MainState::VERSION, MushiProgramError::InvalidAccountVersion);
+    if main_state.version < 3 {
+        // zeroed limits would freeze the fees
+        main_state.fee_change_limits = FeeChangeLimits::DEFAULT;
+    }
+    main_state.version = MainState::VERSION;
+    main_state.exit(&crate::ID)?;
+
+    emit!(AccountMigrated { account: account.key(), version: MainState::VERSION });
+    Ok(())
+}
+
+pub fn migrate_global_state(ctx: Context<AMigrateGlobalState>) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let account = ctx.accounts.global_state.to_account_info();
+    realloc_account(
+        account.clone(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        8 + GlobalStats::MAX_SIZE,
+    )?;
+    let mut global_state: Account<GlobalStats> = Account::try_from(&account)?;
+    require!(global_state.version <= GlobalStats::VERSION, MushiProgramError::InvalidAccountVersion);
+    if global_state.version == 0 {
+        // before the backing field the price was read from the vault balance,
+        // nothing else was held there yet
+        global_state.backing = ctx
+            .accounts
+            .token_vault_owner
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+    }
+    // the fields added by v2 and v3 are correct zeroed: no relaunch scheduled,
+    // trading already open and no anti-bot window
+    global_state.version = GlobalStats::VERSION;
+    global_state.exit(&crate::ID)?;
+
+    emit!(AccountMigrated { account: account.key(), version: GlobalStats::VERSION });
+    Ok(())
+}
+
+/// Permissionless, `payer` covers the extra rent of the user's loan account
+pub fn migrate_user_loan(ctx: Context<AMigrateUserLoan>) -> Result<()> {
+    let account = ctx.accounts.user_loan.to_account_info();
+    realloc_account(
+        account.clone(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        8 + UserLoan::MAX_SIZE,
+    )?;
+    let mut user_loan: Account<UserLoan> = Account::try_from(&account)?;
+    require!(user_loan.version <= UserLoan::VERSION, MushiProgramError::InvalidAccountVersion);
+    user_loan.version = UserLoan::VERSION;
+    user_loan.exit(&crate::ID)?;
+
+    emit!(AccountMigrated { account: account.key(), version: UserLoan::VERSION });
+    Ok(())
+}
+
+/// Permissionless, migrates the daily stats of the day containing `timestamp`
+pub fn migrate_daily_stats(ctx: Context<AMigrateDailyStats>, _timestamp: i64) -> Result<()> {
+    let account = ctx.accounts.daily_state.to_account_info();
+    realloc_account(
+        account.clone(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        8 + DailyStats::MAX_SIZE,
+    )?;
+    let mut daily_state: Account<DailyStats> = Account::try_from(&account)?;
+    require!(daily_state.version <= DailyStats::VERSION, MushiProgramError::InvalidAccountVersion);
+    daily_state.version = DailyStats::VERSION;
+    daily_state.exit(&crate::ID)?;
+
+    emit!(AccountMigrated { account: account.key(), version: DailyStats::VERSION });
+    Ok(())
+}
+
+/// Permissionless, `payer` covers the extra rent of the user's launch buyer account
+pub fn migrate_launch_buyer(ctx: Context<AMigrateLaunchBuyer>) -> Result<()> {
+    let account = ctx.accounts.launch_buyer.to_account_info();
+    realloc_account(
+        account.clone(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        8 + LaunchBuyer::MAX_SIZE,
+    )?;
+    let mut launch_buyer: Account<LaunchBuyer> = Account::try_from(&account)?;
+    require!(launch_buyer.version <= LaunchBuyer::VERSION, MushiProgramError::InvalidAccountVersion);
+    launch_buyer.version = LaunchBuyer::VERSION;
+    launch_buyer.exit(&crate::ID)?;
+
+    emit!(AccountMigrated { account: account.key(), version: LaunchBuyer::VERSION });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AMigrateMainState<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: deserialized once grown to the current layout
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: UncheckedAccount<'info>,
+    /// Missing on deployments from before the roles
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+        space = 8 + Roles::MAX_SIZE,
+    )]
+    pub roles: Account<'info, Roles>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AMigrateGlobalState<'info> {
+    #[account(
+        mut,
+        constraint = main_state.is_admin(admin.key) @MushiProgramError::UnAuthorised
+    )]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    /// CHECK: deserialized once grown to the current layout
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: UncheckedAccount<'info>,
+    #[account(
+        seeds = [VAULT_SEED],
+        bump,
+    )]
+    pub token_vault_owner: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AMigrateUserLoan<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: only used to derive the loan address
+    pub user: UncheckedAccount<'info>,
+    /// CHECK: deserialized once grown to the current layout
+    #[account(
+        mut,
+        seeds = [
+            b"user-loan".as_ref(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_loan: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(timestamp: i64)]
+pub struct AMigrateDailyStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: deserialized once grown to the current layout
+    #[account(
+        mut,
+        seeds = [
+            b"daily-stats".as_ref(),
+            get_date_string_from_timestamp(timestamp).as_bytes()
+        ],
+        bump
+    )]
+    pub daily_state: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AMigrateLaunchBuyer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: only used to derive the launch buyer address
+    pub user: UncheckedAccount<'info>,
+    /// CHECK: deserialized once grown to the current layout
+    #[account(
+        mut,
+        seeds = [LaunchBuyer::PREFIX_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub launch_buyer: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
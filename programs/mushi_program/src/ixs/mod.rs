@@ -4,6 +4,9 @@ pub use start::*;
 pub mod update_main_state;
 pub use update_main_state::*;
 
+pub mod promotion;
+pub use promotion::*;
+
 pub mod buy_sell;
 pub use buy_sell::*;
 
@@ -27,3 +30,33 @@ pub use repay::*;
 
 pub mod remove_collateral;
 pub use remove_collateral::*;
+
+
+pub mod claim_fees;
+pub use claim_fees::*;
+
+pub mod backing;
+pub use backing::*;
+pub mod referral;
+pub use referral::*;
+
+pub mod admin;
+pub use admin::*;
+
+pub mod config_change;
+pub use config_change::*;
+
+pub mod pause;
+pub use pause::*;
+
+pub mod roles;
+pub use roles::*;
+
+pub mod migrate;
+pub use migrate::*;
+
+pub mod relaunch;
+pub use relaunch::*;
+
+pub mod vesting;
+pub use vesting::*;
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::PAUSE_ALL,
+    error::MushiProgramError,
+    events::{Paused, Unpaused},
+    state::{MainState, Role, Roles},
+};
+
+/// Pauses the operations in `flags`, callable by the guardian, the pauser or any admin
+pub fn pause(ctx: Context<APause>, flags: u8) -> Result<()> {
+    require!(flags != 0 && flags & !PAUSE_ALL == 0, MushiProgramError::InvalidInput);
+    let main_state = &mut ctx.accounts.main_state;
+    main_state.pause_flags |= flags;
+
+    emit!(Paused {
+        flags,
+        pause_flags: main_state.pause_flags,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+pub fn unpause(ctx: Context<AUnpause>, flags: u8) -> Result<()> {
+    ctx.accounts.main_state.check_role(
+        &ctx.accounts.roles,
+        Role::Pauser,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    require!(flags != 0 && flags & !PAUSE_ALL == 0, MushiProgramError::InvalidInput);
+    let main_state = &mut ctx.accounts.main_state;
+    main_state.pause_flags &= !flags;
+
+    emit!(Unpaused {
+        flags,
+        pause_flags: main_state.pause_flags,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct APause<'info> {
+    #[account(
+        constraint = main_state.is_admin(authority.key)
+            || main_state.guardian == authority.key()
+            || roles.has_role(Role::Pauser, authority.key) @MushiProgramError::UnAuthorised
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+#[derive(Accounts)]
+pub struct AUnpause<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+}
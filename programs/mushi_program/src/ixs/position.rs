@@ -3,7 +3,7 @@ use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
     constants::{
-        FEES_BUY, FEES_SELL, FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
+        FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
     }, context::{ACommonExtLoan, ACommonExtLoan2, ACommonExtSubLoan}, error::MushiProgramError, utils::{
         burn_tokens, get_interest_fee, get_midnight_timestamp, liquidate, mint_to_tokens_by_main_state, sub_loans_by_date, transfer_sol, transfer_tokens
     }
@@ -23,6 +23,7 @@ pub fn close_position(ctx:Context<ACommonExtSubLoan>, sol_amount: u64)->Result<(
         ctx.accounts.common.system_program.to_account_info(), 
         sol_amount, 
         None)?;
+    ctx.accounts.common.add_backing(sol_amount);
             
     let signer_seeds:&[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
     transfer_tokens(
@@ -49,6 +50,7 @@ pub fn flash_close_position(ctx:Context<ACommonExtSubLoan>)->Result<()>{
     require!(!ctx.accounts.common.is_loan_expired()?, MushiProgramError::LoanExpired);
     let global_state = &mut ctx.accounts.common.global_state;
     liquidate(
+        &ctx.accounts.common.main_state,
         &mut ctx.accounts.common.last_liquidation_date_state,
         global_state,
         ctx.accounts.common.token_vault.to_account_info(),
@@ -73,13 +75,13 @@ pub fn flash_close_position(ctx:Context<ACommonExtSubLoan>)->Result<()>{
     )?;
     ctx.accounts.common.global_state.token_supply = ctx.accounts.common.global_state.token_supply.checked_sub(collateral).unwrap();
 
-    let collateral_in_sonic_after_fee = collateral_in_sol.checked_mul(99).unwrap().checked_div(100).unwrap();
-    let fee = collateral_in_sol / 100;
+    let fee = ctx.accounts.common.flash_close_fee(collateral_in_sol);
+    let collateral_in_sonic_after_fee = collateral_in_sol.checked_sub(fee).unwrap();
 
     require!(collateral_in_sonic_after_fee >= borrowed, MushiProgramError::InvalidCollateralAmount);
     
     let to_user = collateral_in_sonic_after_fee.checked_sub(borrowed).unwrap();
-    let fee_address_fee = fee.checked_mul(3).unwrap().checked_div(10).unwrap();
+    let fee_address_fee = ctx.accounts.common.protocol_fee(fee);
     transfer_sol(
         ctx.accounts.common.token_vault_owner.to_account_info(),
         ctx.accounts.common.user.to_account_info(),
@@ -88,14 +90,8 @@ pub fn flash_close_position(ctx:Context<ACommonExtSubLoan>)->Result<()>{
         Some(signer_seeds)
     )?;
 
-    require!(fee_address_fee > MIN, MushiProgramError::InvalidFeeAmount);
-
-    transfer_sol(
-        ctx.accounts.common.token_vault_owner.to_account_info(),
-        ctx.accounts.common.fee_receiver.to_account_info(),
-        ctx.accounts.common.system_program.to_account_info(),
-        fee_address_fee,
-            Some(signer_seeds))?;
+    ctx.accounts.common.sub_backing(to_user + fee_address_fee);
+    ctx.accounts.common.accrue_protocol_fee(fee_address_fee);
     sub_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_old_end_date, borrowed, collateral)?;
     let user_loan = &mut ctx.accounts.common.user_loan;
     user_loan.borrowed = 0;
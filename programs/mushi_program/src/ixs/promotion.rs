@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FEE_BASE_10000, MAX_TREASURY_FEE_BPS},
+    error::MushiProgramError,
+    events::{PromotionEnded, PromotionScheduled},
+    state::{MainState, Promotion, Role, Roles},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SchedulePromotionInput {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub buy_treasury_bps: u64,
+    pub sell_treasury_bps: u64,
+    pub interest_fee_bps: u64,
+}
+
+pub fn schedule_promotion(
+    ctx: Context<ASchedulePromotion>,
+    input: SchedulePromotionInput,
+) -> Result<()> {
+    ctx.accounts.main_state.check_role(
+        &ctx.accounts.roles,
+        Role::FeeManager,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        input.start_ts < input.end_ts && input.end_ts > now,
+        MushiProgramError::InvalidPromotion
+    );
+    require!(
+        input.buy_treasury_bps <= MAX_TREASURY_FEE_BPS
+            && input.sell_treasury_bps <= MAX_TREASURY_FEE_BPS
+            && input.interest_fee_bps <= FEE_BASE_10000,
+        MushiProgramError::InvalidPromotion
+    );
+
+    let promotion = &mut ctx.accounts.promotion;
+    // flush the events of the previous promotion before replacing it
+    promotion.announce(now);
+    require!(!promotion.is_active(now), MushiProgramError::PromotionInProgress);
+
+    promotion.start_ts = input.start_ts;
+    promotion.end_ts = input.end_ts;
+    promotion.buy_treasury_bps = input.buy_treasury_bps;
+    promotion.sell_treasury_bps = input.sell_treasury_bps;
+    promotion.interest_fee_bps = input.interest_fee_bps;
+    promotion.started_announced = false;
+    promotion.ended_announced = false;
+
+    emit!(PromotionScheduled {
+        start_ts: input.start_ts,
+        end_ts: input.end_ts,
+        buy_treasury_bps: input.buy_treasury_bps,
+        sell_treasury_bps: input.sell_treasury_bps,
+        interest_fee_bps: input.interest_fee_bps,
+    });
+    Ok(())
+}
+
+pub fn cancel_promotion(ctx: Context<ACancelPromotion>) -> Result<()> {
+    ctx.accounts.main_state.check_role(
+        &ctx.accounts.roles,
+        Role::FeeManager,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let now = Clock::get()?.unix_timestamp;
+    let promotion = &mut ctx.accounts.promotion;
+    promotion.announce(now);
+    if promotion.is_active(now) {
+        emit!(PromotionEnded {
+            start_ts: promotion.start_ts,
+            end_ts: now,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ASchedulePromotion<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds=[MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [Promotion::PREFIX_SEED],
+        bump,
+        space = 8 + Promotion::MAX_SIZE,
+    )]
+    pub promotion: Account<'info, Promotion>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ACancelPromotion<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds=[MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [Promotion::PREFIX_SEED],
+        bump,
+    )]
+    pub promotion: Account<'info, Promotion>,
+}
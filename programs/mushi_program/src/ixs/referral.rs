@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::VAULT_SEED,
+    error::MushiProgramError,
+    state::{GlobalStats, ReferralCode, Referrer},
+    utils::transfer_sol,
+};
+
+pub fn register_referrer(ctx: Context<ARegisterReferrer>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.authority = ctx.accounts.authority.key();
+    Ok(())
+}
+
+/// Registers a human readable code for the caller's referrer account
+pub fn register_referral_code(ctx: Context<ARegisterReferralCode>, code: String) -> Result<()> {
+    let code = ReferralCode::normalize(&code)?;
+    let referral_code = &mut ctx.accounts.referral_code;
+    referral_code.referrer = ctx.accounts.authority.key();
+    referral_code.code[..code.len()].copy_from_slice(code.as_bytes());
+    Ok(())
+}
+
+/// Pays the referral rewards accrued in the vault to the referrer
+pub fn claim_referral_rewards(ctx: Context<AClaimReferralRewards>) -> Result<()> {
+    let amount = ctx.accounts.referrer.pending_rewards;
+    require!(amount > 0, MushiProgramError::NothingToClaim);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]];
+    transfer_sol(
+        ctx.accounts.token_vault_owner.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        amount,
+        Some(signer_seeds),
+    )?;
+    ctx.accounts.referrer.pending_rewards = 0;
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.referral_rewards = global_state.referral_rewards.checked_sub(amount).unwrap();
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ARegisterReferrer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [Referrer::PREFIX_SEED, authority.key().as_ref()],
+        bump,
+        space = 8 + Referrer::MAX_SIZE,
+    )]
+    pub referrer: Account<'info, Referrer>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct ARegisterReferralCode<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Referrer::PREFIX_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+    )]
+    pub referrer: Account<'info, Referrer>,
+    /// `init` makes the code unique
+    #[account(
+        init,
+        payer = authority,
+        seeds = [ReferralCode::PREFIX_SEED, ReferralCode::normalize(&code)?.as_bytes()],
+        bump,
+        space = 8 + ReferralCode::MAX_SIZE,
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Referrer::PREFIX_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+    )]
+    pub referrer: Account<'info, Referrer>,
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED],
+        bump,
+    )]
+    pub token_vault_owner: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+use crate::{
+    constants::{RELAUNCH_DELAY, VAULT_SEED},
+    error::MushiProgramError,
+    events::{RelaunchCancelled, RelaunchScheduled, Relaunched},
+    state::{GlobalStats, MainState, TeamVesting},
+};
+
+/// Schedules the reset that lets `start` run again, executable after `RELAUNCH_DELAY`
+pub fn schedule_relaunch(ctx: Context<ARelaunch>) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let global_state = &mut ctx.accounts.global_state;
+    require!(global_state.started, MushiProgramError::NotStarted);
+    require!(global_state.relaunch_eta == 0, MushiProgramError::RelaunchAlreadyScheduled);
+    global_state.relaunch_eta = Clock::get()?.unix_timestamp + RELAUNCH_DELAY;
+
+    emit!(RelaunchScheduled { eta: global_state.relaunch_eta });
+    Ok(())
+}
+
+pub fn cancel_relaunch(ctx: Context<ARelaunch>) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let global_state = &mut ctx.accounts.global_state;
+    require!(global_state.relaunch_eta != 0, MushiProgramError::RelaunchNotScheduled);
+    let eta = std::mem::take(&mut global_state.relaunch_eta);
+
+    emit!(RelaunchCancelled { eta });
+    Ok(())
+}
+
+/// Marks the protocol as not started, refused while any loan is outstanding or any token
+/// of the current mint is held outside the vault and the team vesting. The remaining backing
+/// carries over to the next launch.
+pub fn execute_relaunch(ctx: Context<AExecuteRelaunch>) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let global_state = &mut ctx.accounts.global_state;
+    require!(global_state.relaunch_eta != 0, MushiProgramError::RelaunchNotScheduled);
+    require!(
+        global_state.total_borrowed == 0 && global_state.total_collateral == 0,
+        MushiProgramError::LoansOutstanding
+    );
+    // unclaimed team tokens can't be sold, the team allocation ends with its launch
+    let mut held = ctx.accounts.token_vault.amount;
+    if let Some(team_vesting_vault) = ctx.accounts.team_vesting_vault.as_ref() {
+        let (team_vesting, _) = Pubkey::find_program_address(
+            &[TeamVesting::PREFIX_SEED, ctx.accounts.token.key().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(team_vesting_vault.owner, team_vesting, MushiProgramError::InvalidInput);
+        held += team_vesting_vault.amount;
+    }
+    // every holder must have sold first, the next launch replaces the mint they could sell
+    require!(ctx.accounts.token.supply == held, MushiProgramError::SupplyOutstanding);
+    require!(
+        Clock::get()?.unix_timestamp >= global_state.relaunch_eta,
+        MushiProgramError::RelaunchNotReady
+    );
+    global_state.started = false;
+    global_state.relaunch_eta = 0;
+
+    emit!(Relaunched { previous_token: global_state.token });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ARelaunch<'info> {
+    #[account(constraint = main_state.is_admin(admin.key) @MushiProgramError::UnAuthorised)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct AExecuteRelaunch<'info> {
+    #[account(constraint = main_state.is_admin(admin.key) @MushiProgramError::UnAuthorised)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        mut,
+        seeds = [GlobalStats::PREFIX_SEED],
+        bump,
+    )]
+    pub global_state: Account<'info, GlobalStats>,
+    #[account(address = global_state.token)]
+    pub token: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(
+        seeds = [VAULT_SEED],
+        bump,
+    )]
+    pub token_vault_owner: SystemAccount<'info>,
+    #[account(
+        token::mint = token,
+        token::authority = token_vault_owner,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    /// Holds the unclaimed team allocation, needed when the launch has one
+    #[account(token::mint = token)]
+    pub team_vesting_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
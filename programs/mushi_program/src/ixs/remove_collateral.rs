@@ -3,7 +3,7 @@ use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
     constants::{
-        FEES_BUY, FEES_SELL, FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
+        FEE_BASE_1000, MIN, PAUSE_LOAN_MODIFICATIONS, SECONDS_IN_A_DAY, VAULT_SEED
     }, context::{ACommonExtLoan, ACommonExtSubLoan}, error::MushiProgramError, utils::{
         burn_tokens, get_interest_fee, get_midnight_timestamp, liquidate, mint_to_tokens_by_main_state, sub_loans_by_date, transfer_sol, transfer_tokens
     }
@@ -11,10 +11,12 @@ use crate::{
 use crate::context::common::ACommon;
 
 pub fn remove_collateral(ctx:Context<ACommonExtSubLoan>, amount: u64)->Result<()>{
+    ctx.accounts.common.main_state.require_not_paused(PAUSE_LOAN_MODIFICATIONS)?;
     require!(!ctx.accounts.common.is_loan_expired()?, MushiProgramError::LoanExpired);
 
     let global_state = &mut ctx.accounts.common.global_state;  
     liquidate(
+        &ctx.accounts.common.main_state,
         &mut ctx.accounts.common.last_liquidation_date_state,
         global_state,
         ctx.accounts.common.token_vault.to_account_info(),
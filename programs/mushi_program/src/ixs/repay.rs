@@ -3,7 +3,7 @@ use anchor_spl::{associated_token::AssociatedToken, token_interface};
 
 use crate::{
     constants::{
-        FEES_BUY, FEES_SELL, FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
+        FEE_BASE_1000, MIN, SECONDS_IN_A_DAY, VAULT_SEED
     }, context::{ACommonExtLoan, ACommonExtSubLoan}, error::MushiProgramError, utils::{
         burn_tokens, get_interest_fee, get_midnight_timestamp, liquidate, mint_to_tokens_by_main_state, sub_loans_by_date, transfer_sol, transfer_tokens
     }
@@ -22,6 +22,7 @@ pub fn repay(ctx:Context<ACommonExtSubLoan>, sol_amount: u64)->Result<()>{
         ctx.accounts.common.system_program.to_account_info(), 
         sol_amount, 
         None)?;
+    ctx.accounts.common.add_backing(sol_amount);
 
     sub_loans_by_date(&mut ctx.accounts.common.global_state, &mut ctx.accounts.daily_state_old_end_date, sol_amount, 0)?;
     let new_borrow = borrowed - sol_amount;
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::MushiProgramError,
+    events::{RoleGranted, RoleRevoked},
+    state::{MainState, Role, Roles},
+};
+
+/// Hands `role` to `account`, replacing its previous holder
+pub fn grant_role(ctx: Context<AManageRoles>, role: Role, account: Pubkey) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    require!(account != Pubkey::default(), MushiProgramError::InvalidInput);
+    let holder = ctx.accounts.roles.holder_mut(role);
+    let previous = std::mem::replace(holder, account);
+
+    emit!(RoleGranted { role, account, previous });
+    Ok(())
+}
+
+pub fn revoke_role(ctx: Context<AManageRoles>, role: Role) -> Result<()> {
+    ctx.accounts.main_state.check_admin_signers(&ctx.accounts.admin.to_account_info(), ctx.remaining_accounts)?;
+    let holder = ctx.accounts.roles.holder_mut(role);
+    let account = std::mem::take(holder);
+
+    emit!(RoleRevoked { role, account });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AManageRoles<'info> {
+    #[account(constraint = main_state.is_admin(admin.key) @MushiProgramError::UnAuthorised)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Account<'info, MainState>,
+    #[account(
+        mut,
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+}
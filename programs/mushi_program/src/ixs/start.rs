@@ -12,10 +12,15 @@ use mpl_token_metadata::{
 };
 
 use crate::{
-    constants::{LAMPORTS_PER_SOL, MIN, SECONDS_IN_A_DAY, VAULT_SEED},
+    constants::{
+        FEE_BASE_10000, LAMPORTS_PER_SOL, MAX_INITIAL_BURN_TOKEN_AMOUNT, MAX_SELLER_FEE_BPS, MAX_TEAM_ALLOCATION_BPS, MAX_TEAM_VESTING_DURATION,
+        MAX_TRADING_START_DELAY, MAX_ANTI_BOT_DURATION,
+        MAX_TOKENS_PER_SOL, MIN_INITIALIZE_TOKEN_AMOUNT, MIN_TOKENS_PER_SOL, VAULT_SEED,
+    },
     error::MushiProgramError,
     program::MushiProgram,
     utils::{burn_tokens, mint_to_tokens_by_main_state, transfer_sol},
+    state::{Role, Roles, TeamVesting},
     MainState, GlobalStats,
 };
 
@@ -25,16 +30,85 @@ pub struct StartInput {
     pub token_name: String,
     pub token_symbol: String,
     pub token_uri: String,
+    /// Initial price, in token base units minted per SOL deposited
+    pub tokens_per_sol: u64,
+    /// Burned from the initial mint
+    pub burn_amount: u64,
+    /// Minted on top of the initial mint and locked in the team vesting, counted in the supply
+    pub team_allocation: u64,
+    /// Seconds from the launch until the team allocation starts to release
+    pub team_cliff_duration: i64,
+    /// Seconds from the launch until the team allocation is fully released
+    pub team_vesting_duration: i64,
+    pub seller_fee_basis_points: u16,
+    pub is_mutable: bool,
+    /// When buys open, 0 to open them right away
+    pub trading_start_ts: i64,
+    /// Seconds after `trading_start_ts` during which each wallet can buy once, up to `anti_bot_max_buy`
+    pub anti_bot_duration: i64,
+    pub anti_bot_max_buy: u64,
+}
+
+impl StartInput {
+    /// Returns the amount minted to the vault against `sol_amount`
+    pub fn validate(&self, now: i64) -> Result<u64> {
+        require!(
+            (MIN_TOKENS_PER_SOL..=MAX_TOKENS_PER_SOL).contains(&self.tokens_per_sol),
+            MushiProgramError::InvalidInitialPrice
+        );
+        let initial_mint = (self.sol_amount as u128 * self.tokens_per_sol as u128 / LAMPORTS_PER_SOL as u128) as u64;
+        require!(initial_mint >= MIN_INITIALIZE_TOKEN_AMOUNT, MushiProgramError::InvalidInitialPrice);
+        require!(
+            self.burn_amount <= initial_mint.min(MAX_INITIAL_BURN_TOKEN_AMOUNT),
+            MushiProgramError::InvalidBurnAmount
+        );
+        require!(
+            self.team_allocation as u128 * FEE_BASE_10000 as u128
+                <= initial_mint as u128 * MAX_TEAM_ALLOCATION_BPS as u128,
+            MushiProgramError::InvalidTeamAllocation
+        );
+        require!(
+            self.team_allocation == 0
+                || (0 <= self.team_cliff_duration
+                    && self.team_cliff_duration <= self.team_vesting_duration
+                    && (1..=MAX_TEAM_VESTING_DURATION).contains(&self.team_vesting_duration)),
+            MushiProgramError::InvalidVestingSchedule
+        );
+        require!(self.seller_fee_basis_points <= MAX_SELLER_FEE_BPS, MushiProgramError::InvalidSellerFee);
+        require!(
+            (self.trading_start_ts == 0 || (now..=now + MAX_TRADING_START_DELAY).contains(&self.trading_start_ts))
+                && (0..=MAX_ANTI_BOT_DURATION).contains(&self.anti_bot_duration)
+                && (self.anti_bot_duration == 0 || self.anti_bot_max_buy > 0),
+            MushiProgramError::InvalidLaunchSchedule
+        );
+        Ok(initial_mint)
+    }
 }
 
 pub fn start(ctx: Context<AStart>, input: StartInput) -> Result<()> {
+    ctx.accounts.main_state.check_role(
+        &ctx.accounts.roles,
+        Role::LaunchOperator,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
     let main_state = &mut ctx.accounts.main_state;
     let global_state = &mut ctx.accounts.global_state;
     let mint = ctx.accounts.token.to_account_info();
-    let admin = ctx.accounts.admin.to_account_info();
+    let authority = ctx.accounts.authority.to_account_info();
     //checks
-    let team_mint_amount = input.sol_amount * MIN;
-    require!(team_mint_amount >= LAMPORTS_PER_SOL, MushiProgramError::InvalidInput);
+    let now = Clock::get()?.unix_timestamp;
+    let initial_mint = input.validate(now)?;
+    // the team accounts are created, and their rent charged, only for a team allocation
+    let has_team_allocation = input.team_allocation > 0;
+    require!(
+        ctx.accounts.team_wallet.is_some() == has_team_allocation
+            && ctx.accounts.team_vesting.is_some() == has_team_allocation
+            && ctx.accounts.team_vesting_vault.is_some() == has_team_allocation,
+        MushiProgramError::InvalidInput
+    );
+    // a second launch would replace the mint and strand every holder and loan
+    require!(!global_state.started, MushiProgramError::AlreadyStarted);
 
     let token_vault = ctx.accounts.token_vault.to_account_info();
     let token_program = ctx.accounts.token_program.to_account_info();
@@ -44,9 +118,31 @@ pub fn start(ctx: Context<AStart>, input: StartInput) -> Result<()> {
         main_state.to_account_info(),
         token_vault.to_account_info(),
         token_program.to_account_info(),
-        team_mint_amount,
+        initial_mint,
         *ctx.bumps.get("main_state").unwrap(),
     )?;
+    if input.team_allocation > 0 {
+        let (Some(team_wallet), Some(team_vesting), Some(team_vesting_vault)) = (
+            ctx.accounts.team_wallet.as_ref(),
+            ctx.accounts.team_vesting.as_mut(),
+            ctx.accounts.team_vesting_vault.as_ref(),
+        ) else {
+            return err!(MushiProgramError::InvalidInput);
+        };
+        mint_to_tokens_by_main_state(
+            mint.to_account_info(),
+            main_state.to_account_info(),
+            team_vesting_vault.to_account_info(),
+            token_program.to_account_info(),
+            input.team_allocation,
+            *ctx.bumps.get("main_state").unwrap(),
+        )?;
+        team_vesting.beneficiary = team_wallet.key();
+        team_vesting.total = input.team_allocation;
+        team_vesting.start_ts = now;
+        team_vesting.cliff_ts = now + input.team_cliff_duration;
+        team_vesting.end_ts = now + input.team_vesting_duration;
+    }
 
     // burn tokens
     burn_tokens(
@@ -54,31 +150,37 @@ pub fn start(ctx: Context<AStart>, input: StartInput) -> Result<()> {
         mint.to_account_info(),
         ctx.accounts.token_vault_owner.to_account_info(),
         token_program.to_account_info(),
-        1 * LAMPORTS_PER_SOL,
+        input.burn_amount,
         Some(&[&[VAULT_SEED, &[*ctx.bumps.get("token_vault_owner").unwrap()]]]),
     )?;
-    global_state.token_supply = team_mint_amount;
+    global_state.token_supply = initial_mint + input.team_allocation;
     global_state.started = true;
     global_state.token = mint.key();
     global_state.total_borrowed = 0;
     global_state.total_collateral = 0;
     global_state.last_price = 0;
+    // backing left by an earlier launch stays with the vault
+    global_state.backing += input.sol_amount;
+    global_state.trading_start_ts = if input.trading_start_ts == 0 { now } else { input.trading_start_ts };
+    global_state.anti_bot_end_ts = global_state.trading_start_ts + input.anti_bot_duration;
+    global_state.anti_bot_max_buy = input.anti_bot_max_buy;
     
     msg!(&mint.key().to_string());
-    // transfer sol to vault owner
+    // transfer sol to vault owner, topping it up to rent exemption so the rent is never counted as backing
+    let vault_rent = Rent::get()?.minimum_balance(0).saturating_sub(ctx.accounts.token_vault_owner.lamports());
     let system_program = ctx.accounts.system_program.to_account_info();
     transfer_sol(
-        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_vault_owner.to_account_info(), 
         system_program.to_account_info(), 
-        input.sol_amount, 
+        input.sol_amount + vault_rent, 
         None)?;
     // set token metadata
     let set_metadata_ix = CreateMetadataAccountV3 {
         metadata: ctx.accounts.token_metadata_account.key(),
         mint: mint.key(),
         mint_authority: main_state.key(),
-        payer: ctx.accounts.admin.key(),
+        payer: ctx.accounts.authority.key(),
         rent: Some(ctx.accounts.sysvar_rent.key()),
         system_program: ctx.accounts.system_program.key(),
         update_authority: (main_state.key(), true),
@@ -93,18 +195,18 @@ pub fn start(ctx: Context<AStart>, input: StartInput) -> Result<()> {
                 share: 100,
                 verified: true,
             }]),
-            seller_fee_basis_points: 100,
+            seller_fee_basis_points: input.seller_fee_basis_points,
             collection: None,
             uses: None,
         },
-        is_mutable: false,
+        is_mutable: input.is_mutable,
         collection_details: None,
     });
     invoke_signed(
         &set_metadata_ix,
         &[
             main_state.to_account_info(),
-            admin.clone(),
+            authority.clone(),
             mint.clone(),
             ctx.accounts.token_metadata_account.to_account_info(),
             ctx.accounts.mpl_program.to_account_info(),
@@ -122,11 +224,8 @@ pub fn start(ctx: Context<AStart>, input: StartInput) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct AStart<'info> {
-    #[account(
-        mut,
-        address=main_state.admin @MushiProgramError::UnAuthorised
-    )]
-    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
     #[account(
         mut,
         seeds=[MainState::PREFIX_SEED],
@@ -139,9 +238,14 @@ pub struct AStart<'info> {
         bump,
     )]
     pub global_state: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
     #[account(
         init,
-        payer = admin,
+        payer = authority,
         signer,
         mint::decimals = 6,
         mint::authority = main_state,
@@ -165,11 +269,28 @@ pub struct AStart<'info> {
     pub token_vault_owner: SystemAccount<'info>,
     #[account(
         init,
-        payer = admin,
+        payer = authority,
         associated_token::mint = token,
         associated_token::authority = token_vault_owner,
     )]
     pub token_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: beneficiary of the team allocation, the team accounts are passed only with a team allocation
+    pub team_wallet: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [TeamVesting::PREFIX_SEED, token.key().as_ref()],
+        bump,
+        space = 8 + TeamVesting::MAX_SIZE,
+    )]
+    pub team_vesting: Option<Box<Account<'info, TeamVesting>>>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = token,
+        associated_token::authority = team_vesting,
+    )]
+    pub team_vesting_vault: Option<Box<Account<'info, TokenAccount>>>,
     ///CHECK:
     pub sysvar_rent: AccountInfo<'info>,
     ///CHECK:
@@ -1,47 +1,130 @@
 use anchor_lang::prelude::*;
 
-use crate::{error::MushiProgramError, state::MainState};
+use crate::{
+    constants::{MAX_FEE_RECIPIENTS, MAX_FEE_TIERS, SECONDS_IN_A_DAY},
+    error::MushiProgramError,
+    state::{FeeChangeLimits, FeeRecipient, FeeSchedule, FeeTier, MainState, Role, Roles},
+};
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone)]
+// `max_len` only takes literals
+const _: () = assert!(MAX_FEE_RECIPIENTS == 4);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, InitSpace)]
 pub struct UpdateMainStateInput {
-    admin: Option<Pubkey>,
-    fee_receiver: Option<Pubkey>,
-    sell_fee: Option<u64>,
-    buy_fee: Option<u64>,
+    #[max_len(4)]
+    fee_recipients: Option<Vec<FeeRecipient>>,
+    fee_schedule: Option<FeeSchedule>,
     buy_fee_leverage: Option<u64>,
+    protocol_fee_share: Option<u64>,
+    flash_close_fee: Option<u64>,
+    fee_tiers: Option<[FeeTier; MAX_FEE_TIERS]>,
+    referral_binding_duration: Option<u64>,
+    leverage_referral_share: Option<u64>,
+    loan_referral_share: Option<u64>,
+    max_integrator_fee_bps: Option<u64>,
+    fee_change_limits: Option<FeeChangeLimits>,
+    config_timelock: Option<u64>,
+    guardian: Option<Pubkey>,
+    /// All zero to end the presale
+    presale_root: Option<[u8; 32]>,
 }
 
+impl UpdateMainStateInput {
+    /// Fee settings need the fee manager, everything else the config manager
+    pub fn required_roles(&self) -> Vec<Role> {
+        let mut roles = Vec::with_capacity(2);
+        if self.fee_recipients.is_some()
+            || self.fee_schedule.is_some()
+            || self.buy_fee_leverage.is_some()
+            || self.protocol_fee_share.is_some()
+            || self.flash_close_fee.is_some()
+            || self.fee_tiers.is_some()
+            || self.leverage_referral_share.is_some()
+            || self.loan_referral_share.is_some()
+            || self.max_integrator_fee_bps.is_some()
+            || self.fee_change_limits.is_some()
+        {
+            roles.push(Role::FeeManager);
+        }
+        if roles.is_empty()
+            || self.referral_binding_duration.is_some()
+            || self.config_timelock.is_some()
+            || self.guardian.is_some()
+            || self.presale_root.is_some()
+        {
+            roles.push(Role::ConfigManager);
+        }
+        roles
+    }
+
+    /// Checks that `authority` holds every role the change needs, or that enough admins signed
+    pub(crate) fn check_roles(
+        &self,
+        main_state: &MainState,
+        roles: &Roles,
+        authority: &AccountInfo,
+        co_signers: &[AccountInfo],
+    ) -> Result<()> {
+        for role in self.required_roles() {
+            main_state.check_role(roles, role, authority, co_signers)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the changes directly, only possible while no timelock is configured
 pub fn update_main_state(
     ctx: Context<AUpdateMainState>,
     input: UpdateMainStateInput,
 ) -> Result<()> {
+    input.check_roles(
+        &ctx.accounts.main_state,
+        &ctx.accounts.roles,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
     let state = &mut ctx.accounts.main_state;
-    state.admin = input.admin.unwrap_or(state.admin);
-    state.fee_receiver = input.fee_receiver.unwrap_or(state.fee_receiver);
-
-    let buy_fee = input.buy_fee.unwrap_or(state.buy_fee);
-    
-    require!(buy_fee <= 992 && buy_fee >= 975, MushiProgramError::InvalidBuyFee);
-    state.buy_fee = buy_fee;
-
-    let sell_fee = input.sell_fee.unwrap_or(state.sell_fee);
-    require!(sell_fee <= 992 && sell_fee >= 975, MushiProgramError::InvalidSellFee);
-    state.sell_fee = input.sell_fee.unwrap_or(state.sell_fee);
-
-    let buy_fee_leverage = input.buy_fee_leverage.unwrap_or(state.buy_fee_leverage);
-    require!(buy_fee_leverage <= 25, MushiProgramError::InvalidBuyFeeLeverage);
-    state.buy_fee_leverage = buy_fee_leverage;
-    Ok(())
+    require!(state.config_timelock == 0, MushiProgramError::ConfigTimelocked);
+    apply_main_state_update(state, input)
+}
+
+/// Applies the set fields of `input` and validates the resulting configuration
+pub(crate) fn apply_main_state_update(state: &mut MainState, input: UpdateMainStateInput) -> Result<()> {
+    let previous_fees = state.fee_snapshot(Clock::get()?.unix_timestamp / SECONDS_IN_A_DAY);
+    let previous_limits = state.fee_change_limits;
+    if let Some(fee_recipients) = input.fee_recipients {
+        state.set_fee_recipients(&fee_recipients)?;
+    }
+    state.fee_schedule = input.fee_schedule.unwrap_or(state.fee_schedule);
+    state.buy_fee_leverage = input.buy_fee_leverage.unwrap_or(state.buy_fee_leverage);
+    state.protocol_fee_share = input.protocol_fee_share.unwrap_or(state.protocol_fee_share);
+    state.flash_close_fee = input.flash_close_fee.unwrap_or(state.flash_close_fee);
+    state.fee_tiers = input.fee_tiers.unwrap_or(state.fee_tiers);
+    state.referral_binding_duration = input.referral_binding_duration.unwrap_or(state.referral_binding_duration);
+    state.leverage_referral_share = input.leverage_referral_share.unwrap_or(state.leverage_referral_share);
+    state.loan_referral_share = input.loan_referral_share.unwrap_or(state.loan_referral_share);
+    state.max_integrator_fee_bps = input.max_integrator_fee_bps.unwrap_or(state.max_integrator_fee_bps);
+    state.fee_change_limits = input.fee_change_limits.unwrap_or(state.fee_change_limits);
+    state.config_timelock = input.config_timelock.unwrap_or(state.config_timelock);
+    state.guardian = input.guardian.unwrap_or(state.guardian);
+    state.presale_root = input.presale_root.unwrap_or(state.presale_root);
+
+    state.validate_config()?;
+    state.check_fee_change_limits(previous_fees, previous_limits)
 }
 
 #[derive(Accounts)]
 pub struct AUpdateMainState<'info> {
-    #[account(address=main_state.admin @MushiProgramError::UnAuthorised)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
     #[account(
         mut,
         seeds=[MainState::PREFIX_SEED],
         bump,
     )]
     pub main_state: Account<'info, MainState>,
+    #[account(
+        seeds = [Roles::PREFIX_SEED],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
 }
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, TokenAccount},
+};
+
+use crate::{
+    error::MushiProgramError,
+    events::VestedClaimed,
+    state::TeamVesting,
+    utils::transfer_tokens,
+};
+
+/// Sends the vested and not yet claimed part of the team allocation to the beneficiary
+pub fn claim_vested(ctx: Context<AClaimVested>) -> Result<()> {
+    let team_vesting = &mut ctx.accounts.team_vesting;
+    let amount = team_vesting.vested(Clock::get()?.unix_timestamp) - team_vesting.claimed;
+    require!(amount > 0, MushiProgramError::NothingToClaim);
+    team_vesting.claimed += amount;
+    let claimed = team_vesting.claimed;
+
+    transfer_tokens(
+        ctx.accounts.team_vesting_vault.to_account_info(),
+        ctx.accounts.beneficiary_ata.to_account_info(),
+        ctx.accounts.team_vesting.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        Some(&[&[
+            TeamVesting::PREFIX_SEED,
+            ctx.accounts.token.key().as_ref(),
+            &[*ctx.bumps.get("team_vesting").unwrap()],
+        ]]),
+    )?;
+
+    emit!(VestedClaimed {
+        beneficiary: ctx.accounts.beneficiary.key(),
+        amount,
+        claimed,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AClaimVested<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        has_one = beneficiary @MushiProgramError::UnAuthorised,
+        seeds = [TeamVesting::PREFIX_SEED, token.key().as_ref()],
+        bump,
+    )]
+    pub team_vesting: Box<Account<'info, TeamVesting>>,
+    /// Mint of the launch the allocation was made in, earlier launches included
+    pub token: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token,
+        associated_token::authority = team_vesting,
+    )]
+    pub team_vesting_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = token,
+        associated_token::authority = beneficiary,
+    )]
+    pub beneficiary_ata: Box<Account<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+}
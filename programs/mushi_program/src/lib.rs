@@ -12,6 +12,7 @@ use state::*;
 
 mod constants;
 mod error;
+mod events;
 mod utils;
 
 declare_id!("HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi");
@@ -31,20 +32,135 @@ pub mod mushi_program {
         ixs::update_main_state(ctx, input)
     }
 
+    pub fn propose_config_change(ctx: Context<AProposeConfigChange>, input: UpdateMainStateInput) -> Result<()> {
+        ixs::propose_config_change(ctx, input)
+    }
+
+    pub fn execute_config_change(ctx: Context<AExecuteConfigChange>) -> Result<()> {
+        ixs::execute_config_change(ctx)
+    }
+
+    pub fn cancel_config_change(ctx: Context<ACancelConfigChange>) -> Result<()> {
+        ixs::cancel_config_change(ctx)
+    }
+
+    pub fn schedule_promotion(
+        ctx: Context<ASchedulePromotion>,
+        input: SchedulePromotionInput,
+    ) -> Result<()> {
+        ixs::schedule_promotion(ctx, input)
+    }
+
+    pub fn cancel_promotion(ctx: Context<ACancelPromotion>) -> Result<()> {
+        ixs::cancel_promotion(ctx)
+    }
+
+    pub fn propose_admins(ctx: Context<AAdminTransfer>, admins: Vec<Pubkey>, threshold: u64) -> Result<()> {
+        ixs::propose_admins(ctx, admins, threshold)
+    }
+
+    pub fn accept_admins(ctx: Context<AAcceptAdmins>) -> Result<()> {
+        ixs::accept_admins(ctx)
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<AAdminTransfer>) -> Result<()> {
+        ixs::cancel_admin_transfer(ctx)
+    }
+
+    pub fn grant_role(ctx: Context<AManageRoles>, role: Role, account: Pubkey) -> Result<()> {
+        ixs::grant_role(ctx, role, account)
+    }
+
+    pub fn revoke_role(ctx: Context<AManageRoles>, role: Role) -> Result<()> {
+        ixs::revoke_role(ctx, role)
+    }
+
+    pub fn pause(ctx: Context<APause>, flags: u8) -> Result<()> {
+        ixs::pause(ctx, flags)
+    }
+
+    pub fn unpause(ctx: Context<AUnpause>, flags: u8) -> Result<()> {
+        ixs::unpause(ctx, flags)
+    }
+
+    pub fn schedule_relaunch(ctx: Context<ARelaunch>) -> Result<()> {
+        ixs::schedule_relaunch(ctx)
+    }
+
+    pub fn cancel_relaunch(ctx: Context<ARelaunch>) -> Result<()> {
+        ixs::cancel_relaunch(ctx)
+    }
+
+    pub fn execute_relaunch(ctx: Context<AExecuteRelaunch>) -> Result<()> {
+        ixs::execute_relaunch(ctx)
+    }
+
+    pub fn migrate_main_state(ctx: Context<AMigrateMainState>) -> Result<()> {
+        ixs::migrate_main_state(ctx)
+    }
+
+    pub fn migrate_global_state(ctx: Context<AMigrateGlobalState>) -> Result<()> {
+        ixs::migrate_global_state(ctx)
+    }
+
+    pub fn migrate_user_loan(ctx: Context<AMigrateUserLoan>) -> Result<()> {
+        ixs::migrate_user_loan(ctx)
+    }
+
+    pub fn migrate_daily_stats(ctx: Context<AMigrateDailyStats>, timestamp: i64) -> Result<()> {
+        ixs::migrate_daily_stats(ctx, timestamp)
+    }
+
+    pub fn migrate_launch_buyer(ctx: Context<AMigrateLaunchBuyer>) -> Result<()> {
+        ixs::migrate_launch_buyer(ctx)
+    }
+
     pub fn start(ctx: Context<AStart>, input: StartInput) -> Result<()> {
         ixs::start(ctx, input)
     }
 
-    pub fn buy(ctx: Context<ACommon>, sol_amount: u64) -> Result<()> {
-        ixs::buy(ctx, sol_amount)
+    pub fn claim_fees<'info>(ctx: Context<'_, '_, '_, 'info, AClaimFees<'info>>) -> Result<()> {
+        ixs::claim_fees(ctx)
+    }
+
+    pub fn claim_vested(ctx: Context<AClaimVested>) -> Result<()> {
+        ixs::claim_vested(ctx)
+    }
+
+    pub fn donate_to_backing(ctx: Context<ADonateToBacking>, sol_amount: u64) -> Result<()> {
+        ixs::donate_to_backing(ctx, sol_amount)
+    }
+
+    pub fn sync_backing(ctx: Context<ASyncBacking>) -> Result<()> {
+        ixs::sync_backing(ctx)
+    }
+
+    pub fn register_referrer(ctx: Context<ARegisterReferrer>) -> Result<()> {
+        ixs::register_referrer(ctx)
+    }
+
+    pub fn register_referral_code(ctx: Context<ARegisterReferralCode>, code: String) -> Result<()> {
+        ixs::register_referral_code(ctx, code)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<AClaimReferralRewards>) -> Result<()> {
+        ixs::claim_referral_rewards(ctx)
+    }
+
+    pub fn buy(ctx: Context<ACommon>, sol_amount: u64, integrator_fee_bps: u64) -> Result<()> {
+        ixs::buy(ctx, sol_amount, integrator_fee_bps)
+    }
+
+    pub fn presale_buy(ctx: Context<ACommon>, input: PresaleBuyInput) -> Result<()> {
+        ixs::presale_buy(ctx, input)
     }
 
     pub fn buy_with_referral(ctx: Context<ACommonExtReferral>, input: BuyWithReferralInput) -> Result<()> {
         ixs::buy_with_referral(ctx, input)
     }
 
-    pub fn sell(ctx: Context<ACommon>, token_amount: u64) -> Result<()> {
-        ixs::sell(ctx, token_amount)
+    pub fn sell(ctx: Context<ACommon>, token_amount: u64, integrator_fee_bps: u64) -> Result<()> {
+        ixs::sell(ctx, token_amount, integrator_fee_bps)
     }
 
     pub fn borrow(ctx: Context<ACommonExtLoan>, number_of_days: u64, sol_amount: u64) -> Result<()> {
@@ -59,8 +175,8 @@ pub mod mushi_program {
         ixs::repay(ctx, sol_amount)
     }
 
-    pub fn leverage(ctx: Context<ACommonExtLoan>, number_of_days: u64, sol_amount: u64) -> Result<()> {
-        ixs::leverage(ctx, number_of_days, sol_amount)
+    pub fn leverage(ctx: Context<ACommonExtLoan>, number_of_days: u64, sol_amount: u64, integrator_fee_bps: u64) -> Result<()> {
+        ixs::leverage(ctx, number_of_days, sol_amount, integrator_fee_bps)
     }
 
     pub fn remove_collateral(ctx: Context<ACommonExtSubLoan>, amount: u64) -> Result<()> {
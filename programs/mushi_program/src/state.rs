@@ -2,51 +2,644 @@ use anchor_lang::prelude::*;
 
 use crate::{
     constants::{
-        SECONDS_IN_A_DAY, FEE_BASE_1000,
+        SECONDS_IN_A_DAY, FEE_BASE_1000, MAX_BACKING_FEE_BPS, MAX_REFERRAL_FEE_BPS, MAX_TOTAL_TRADE_FEE_BPS,
+        MAX_TREASURY_FEE_BPS, MIN_BACKING_FEE_BPS, MAX_FEE_TIERS, MAX_FEE_TIER_DISCOUNT_BPS, VOLUME_WINDOW_DAYS,
+        FEE_BASE_10000, MAX_FEE_RECIPIENTS, MAX_ADMINS, MIN_REFERRAL_CODE_LEN, MAX_REFERRAL_CODE_LEN,
+        MAX_DAILY_TRADE_FEE_CHANGE_BPS, MAX_DAILY_BUY_FEE_LEVERAGE_CHANGE, MAX_DAILY_PROTOCOL_FEE_SHARE_CHANGE,
+        MAX_DAILY_FLASH_CLOSE_FEE_CHANGE, DEFAULT_DAILY_TRADE_FEE_CHANGE_BPS, DEFAULT_DAILY_BUY_FEE_LEVERAGE_CHANGE,
+        DEFAULT_DAILY_PROTOCOL_FEE_SHARE_CHANGE, DEFAULT_DAILY_FLASH_CLOSE_FEE_CHANGE, MAX_BUY_FEE_LEVERAGE, MIN_PROTOCOL_FEE_SHARE, MAX_PROTOCOL_FEE_SHARE,
+        MIN_FLASH_CLOSE_FEE, MAX_FLASH_CLOSE_FEE, MAX_REFERRAL_BINDING_DURATION, MAX_REFERRAL_FEE_SHARE,
+        MAX_INTEGRATOR_FEE_BPS, MAX_CONFIG_TIMELOCK, VAULT_SEED,
     },
     error::MushiProgramError,
+    events::{PromotionEnded, PromotionStarted},
+    ixs::UpdateMainStateInput,
 };
 
+/// Trade fees in basis points of the traded SOL amount.
+/// The referral part goes to the treasury when the trade has no referrer.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct FeeSchedule {
+    pub buy_treasury_bps: u64,
+    pub buy_referral_bps: u64,
+    pub buy_backing_bps: u64,
+    pub sell_treasury_bps: u64,
+    pub sell_referral_bps: u64,
+    pub sell_backing_bps: u64,
+}
+
+impl FeeSchedule {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            Self::is_valid_side(self.buy_treasury_bps, self.buy_referral_bps, self.buy_backing_bps),
+            MushiProgramError::InvalidBuyFee
+        );
+        require!(
+            Self::is_valid_side(self.sell_treasury_bps, self.sell_referral_bps, self.sell_backing_bps),
+            MushiProgramError::InvalidSellFee
+        );
+        Ok(())
+    }
+
+    /// Largest difference between the matching fees of the two schedules
+    pub fn max_change(&self, other: &FeeSchedule) -> u64 {
+        [
+            self.buy_treasury_bps.abs_diff(other.buy_treasury_bps),
+            self.buy_referral_bps.abs_diff(other.buy_referral_bps),
+            self.buy_backing_bps.abs_diff(other.buy_backing_bps),
+            self.sell_treasury_bps.abs_diff(other.sell_treasury_bps),
+            self.sell_referral_bps.abs_diff(other.sell_referral_bps),
+            self.sell_backing_bps.abs_diff(other.sell_backing_bps),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+    }
+
+    fn is_valid_side(treasury_bps: u64, referral_bps: u64, backing_bps: u64) -> bool {
+        treasury_bps <= MAX_TREASURY_FEE_BPS
+            && referral_bps <= MAX_REFERRAL_FEE_BPS
+            && (MIN_BACKING_FEE_BPS..=MAX_BACKING_FEE_BPS).contains(&backing_bps)
+            && treasury_bps + referral_bps + backing_bps <= MAX_TOTAL_TRADE_FEE_BPS
+    }
+}
+
+/// Discount on the treasury fee for users whose trading volume over the
+/// last `VOLUME_WINDOW_DAYS` days is at least `min_volume` lamports.
+/// Tiers with a zero discount are unused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct FeeTier {
+    pub min_volume: u64,
+    pub discount_bps: u64,
+}
+
+pub fn validate_fee_tiers(fee_tiers: &[FeeTier; MAX_FEE_TIERS]) -> Result<()> {
+    let mut previous = FeeTier::default();
+    for tier in fee_tiers.iter().filter(|tier| tier.discount_bps != 0) {
+        require!(
+            tier.discount_bps <= MAX_FEE_TIER_DISCOUNT_BPS
+                && tier.min_volume > previous.min_volume
+                && tier.discount_bps > previous.discount_bps,
+            MushiProgramError::InvalidFeeTiers
+        );
+        previous = *tier;
+    }
+    Ok(())
+}
+
+/// Fee values at the start of `day` (timestamp / SECONDS_IN_A_DAY).
+/// Fee changes are limited by how far they move the fees from these values during the day.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct FeeBaseline {
+    pub day: i64,
+    pub fee_schedule: FeeSchedule,
+    pub buy_fee_leverage: u64,
+    pub protocol_fee_share: u64,
+    pub flash_close_fee: u64,
+}
+
+/// Largest move of each fee away from its `FeeBaseline` within a day
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, InitSpace)]
+pub struct FeeChangeLimits {
+    /// Per trade fee of the fee schedule, in basis points
+    pub trade_fee_bps: u64,
+    pub buy_fee_leverage: u64,
+    pub protocol_fee_share: u64,
+    pub flash_close_fee: u64,
+}
+
+impl FeeChangeLimits {
+    pub const DEFAULT: Self = Self {
+        trade_fee_bps: DEFAULT_DAILY_TRADE_FEE_CHANGE_BPS,
+        buy_fee_leverage: DEFAULT_DAILY_BUY_FEE_LEVERAGE_CHANGE,
+        protocol_fee_share: DEFAULT_DAILY_PROTOCOL_FEE_SHARE_CHANGE,
+        flash_close_fee: DEFAULT_DAILY_FLASH_CLOSE_FEE_CHANGE,
+    };
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.trade_fee_bps <= MAX_DAILY_TRADE_FEE_CHANGE_BPS
+                && self.buy_fee_leverage <= MAX_DAILY_BUY_FEE_LEVERAGE_CHANGE
+                && self.protocol_fee_share <= MAX_DAILY_PROTOCOL_FEE_SHARE_CHANGE
+                && self.flash_close_fee <= MAX_DAILY_FLASH_CLOSE_FEE_CHANGE,
+            MushiProgramError::InvalidFeeChangeLimits
+        );
+        Ok(())
+    }
+}
+
+/// Share of the claimed protocol fees paid to `address`, in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, InitSpace)]
+pub struct FeeRecipient {
+    pub address: Pubkey,
+    pub weight_bps: u64,
+}
+
 #[account]
+#[derive(InitSpace)]
 pub struct MainState {
-    pub admin: Pubkey,
-    pub fee_receiver: Pubkey,
-    pub buy_fee: u64,
-    pub sell_fee: u64,
+    /// Unused slots are left at their default value
+    pub admins: [Pubkey; MAX_ADMINS],
+    /// Number of admins that must sign admin instructions
+    pub admin_threshold: u64,
+    /// Admin set proposed by `propose_admins`, all default when no transfer is pending
+    pub pending_admins: [Pubkey; MAX_ADMINS],
+    pub pending_admin_threshold: u64,
+    /// Unused slots are left at their default value
+    pub fee_recipients: [FeeRecipient; MAX_FEE_RECIPIENTS],
+    pub fee_schedule: FeeSchedule,
     pub buy_fee_leverage: u64,
+    pub protocol_fee_share: u64,
+    pub flash_close_fee: u64,
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    /// Seconds a referral binding lasts, 0 for bindings that never expire
+    pub referral_binding_duration: u64,
+    /// Part of the leverage protocol fee paid to the user's referrer (FEE_BASE_1000)
+    pub leverage_referral_share: u64,
+    /// Part of the borrow and loan extension protocol fees paid to the user's referrer (FEE_BASE_1000)
+    pub loan_referral_share: u64,
+    /// Highest fee integrators can charge on buy, sell and leverage (FEE_BASE_10000)
+    pub max_integrator_fee_bps: u64,
+    /// Seconds between proposing and executing a configuration change,
+    /// `update_main_state` is only usable while it is 0
+    pub config_timelock: u64,
+    /// Can pause but not unpause
+    pub guardian: Pubkey,
+    /// Bitfield of the PAUSE_* constants
+    pub pause_flags: u8,
+    pub fee_baseline: FeeBaseline,
+    /// Layout version, see `MainState::VERSION`
+    pub version: u8,
+    /// Merkle root of the presale allowlist, all zero when there is no presale
+    pub presale_root: [u8; 32],
+    pub fee_change_limits: FeeChangeLimits,
+    /// Room for new fields, zeroed
+    pub reserved: [u8; 32],
 }
 
 impl MainState {
     pub const PREFIX_SEED: &'static [u8] = b"main_state";
-    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();
+    /// 1: versioned layout, 2: `presale_root`, 3: `fee_change_limits`
+    pub const VERSION: u8 = 3;
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.pause_flags & flags != 0
+    }
+
+    pub fn require_not_paused(&self, flags: u8) -> Result<()> {
+        require!(!self.is_paused(flags), MushiProgramError::Paused);
+        Ok(())
+    }
+
+    pub fn fee_snapshot(&self, day: i64) -> FeeBaseline {
+        FeeBaseline {
+            day,
+            fee_schedule: self.fee_schedule,
+            buy_fee_leverage: self.buy_fee_leverage,
+            protocol_fee_share: self.protocol_fee_share,
+            flash_close_fee: self.flash_close_fee,
+        }
+    }
+
+    /// Checks that the current fees stay within the daily change limits,
+    /// `previous` becomes the baseline on the first change of the day.
+    /// `limits` are the ones in force before the update, so raising them only applies to later updates.
+    pub fn check_fee_change_limits(&mut self, previous: FeeBaseline, limits: FeeChangeLimits) -> Result<()> {
+        if self.fee_baseline.day != previous.day {
+            self.fee_baseline = previous;
+        }
+        let baseline = &self.fee_baseline;
+        require!(
+            self.fee_schedule.max_change(&baseline.fee_schedule) <= limits.trade_fee_bps
+                && self.buy_fee_leverage.abs_diff(baseline.buy_fee_leverage) <= limits.buy_fee_leverage
+                && self.protocol_fee_share.abs_diff(baseline.protocol_fee_share) <= limits.protocol_fee_share
+                && self.flash_close_fee.abs_diff(baseline.flash_close_fee) <= limits.flash_close_fee,
+            MushiProgramError::FeeChangeTooLarge
+        );
+        Ok(())
+    }
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.admins.contains(key)
+    }
+
+    pub fn is_pending_admin(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.pending_admins.contains(key)
+    }
+
+    /// Checks that at least `admin_threshold` distinct admins signed, among `admin` and the co-signers
+    pub fn check_admin_signers(&self, admin: &AccountInfo, co_signers: &[AccountInfo]) -> Result<()> {
+        let signers = std::iter::once((admin.key, admin.is_signer))
+            .chain(co_signers.iter().map(|account| (account.key, account.is_signer)));
+        let mut signed: Vec<Pubkey> = Vec::with_capacity(MAX_ADMINS);
+        for (key, is_signer) in signers {
+            if is_signer && self.is_admin(key) && !signed.contains(key) {
+                signed.push(*key);
+            }
+        }
+        require!(signed.len() as u64 >= self.admin_threshold, MushiProgramError::NotEnoughAdminSigners);
+        Ok(())
+    }
+
+    /// Checks that `authority` signed and holds `role`, or that enough admins signed
+    pub fn check_role(&self, roles: &Roles, role: Role, authority: &AccountInfo, co_signers: &[AccountInfo]) -> Result<()> {
+        if authority.is_signer && roles.has_role(role, authority.key) {
+            return Ok(());
+        }
+        require!(self.is_admin(authority.key), MushiProgramError::UnAuthorised);
+        self.check_admin_signers(authority, co_signers)
+    }
+
+    /// Validates an admin set: distinct non-default keys and a threshold between 1 and their count
+    pub fn to_admin_set(admins: &[Pubkey], threshold: u64) -> Result<[Pubkey; MAX_ADMINS]> {
+        require!(
+            !admins.is_empty()
+                && admins.len() <= MAX_ADMINS
+                && (1..=admins.len() as u64).contains(&threshold),
+            MushiProgramError::InvalidAdmins
+        );
+        let mut admin_set = [Pubkey::default(); MAX_ADMINS];
+        for (i, admin) in admins.iter().enumerate() {
+            require!(
+                *admin != Pubkey::default() && !admins[..i].contains(admin),
+                MushiProgramError::InvalidAdmins
+            );
+            admin_set[i] = *admin;
+        }
+        Ok(admin_set)
+    }
+
+    /// Stores the recipients, their addresses and weights are checked by `validate_config`
+    pub fn set_fee_recipients(&mut self, fee_recipients: &[FeeRecipient]) -> Result<()> {
+        // a zero weight marks an unused slot
+        require!(
+            !fee_recipients.is_empty()
+                && fee_recipients.len() <= MAX_FEE_RECIPIENTS
+                && fee_recipients.iter().all(|recipient| recipient.weight_bps != 0),
+            MushiProgramError::InvalidFeeRecipients
+        );
+        self.fee_recipients = [FeeRecipient::default(); MAX_FEE_RECIPIENTS];
+        self.fee_recipients[..fee_recipients.len()].copy_from_slice(fee_recipients);
+        Ok(())
+    }
+
+    /// Validates every configurable parameter, used by `init_main_state` and by configuration updates
+    pub fn validate_config(&self) -> Result<()> {
+        self.validate_fee_recipients()?;
+        self.fee_schedule.validate()?;
+        require!(self.buy_fee_leverage <= MAX_BUY_FEE_LEVERAGE, MushiProgramError::InvalidBuyFeeLeverage);
+        require!(
+            (MIN_PROTOCOL_FEE_SHARE..=MAX_PROTOCOL_FEE_SHARE).contains(&self.protocol_fee_share),
+            MushiProgramError::InvalidProtocolFeeShare
+        );
+        require!(
+            (MIN_FLASH_CLOSE_FEE..=MAX_FLASH_CLOSE_FEE).contains(&self.flash_close_fee),
+            MushiProgramError::InvalidFlashCloseFee
+        );
+        validate_fee_tiers(&self.fee_tiers)?;
+        require!(
+            self.referral_binding_duration <= MAX_REFERRAL_BINDING_DURATION,
+            MushiProgramError::InvalidReferralBindingDuration
+        );
+        require!(
+            self.leverage_referral_share <= MAX_REFERRAL_FEE_SHARE && self.loan_referral_share <= MAX_REFERRAL_FEE_SHARE,
+            MushiProgramError::InvalidReferralFeeShare
+        );
+        require!(self.max_integrator_fee_bps <= MAX_INTEGRATOR_FEE_BPS, MushiProgramError::InvalidIntegratorFee);
+        require!(self.config_timelock <= MAX_CONFIG_TIMELOCK, MushiProgramError::InvalidConfigTimelock);
+        self.fee_change_limits.validate()?;
+        Ok(())
+    }
+
+    fn validate_fee_recipients(&self) -> Result<()> {
+        let recipients: Vec<_> = self.active_fee_recipients().collect();
+        for (i, recipient) in recipients.iter().enumerate() {
+            require!(
+                recipient.address != Pubkey::default()
+                    && recipients[..i].iter().all(|other| other.address != recipient.address),
+                MushiProgramError::InvalidFeeRecipients
+            );
+        }
+        require!(
+            !recipients.is_empty()
+                && recipients.iter().map(|recipient| recipient.weight_bps).sum::<u64>() == FEE_BASE_10000,
+            MushiProgramError::InvalidFeeRecipients
+        );
+        // fees sent to program accounts would be stuck there
+        let vault = Pubkey::find_program_address(&[VAULT_SEED], &crate::ID).0;
+        let main_state = Pubkey::find_program_address(&[Self::PREFIX_SEED], &crate::ID).0;
+        require!(
+            recipients.iter().all(|recipient| recipient.address != vault && recipient.address != main_state),
+            MushiProgramError::InvalidFeeRecipientAddress
+        );
+        Ok(())
+    }
+
+    pub fn active_fee_recipients(&self) -> impl Iterator<Item = &FeeRecipient> {
+        self.fee_recipients.iter().filter(|recipient| recipient.weight_bps != 0)
+    }
+
+    pub fn is_fee_recipient(&self, address: &Pubkey) -> bool {
+        self.active_fee_recipients().any(|recipient| recipient.address == *address)
+    }
+
+    /// Returns the treasury fee discount of the highest tier reached by `volume`
+    pub fn fee_discount_bps(&self, volume: u64) -> u64 {
+        self.fee_tiers
+            .iter()
+            .filter(|tier| tier.discount_bps != 0 && volume >= tier.min_volume)
+            .map(|tier| tier.discount_bps)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserStats {
+    pub total_volume: u64,
+    /// Day (timestamp / SECONDS_IN_A_DAY) of the last recorded trade
+    pub last_trade_day: i64,
+    /// Ring buffer of daily volumes indexed by day % VOLUME_WINDOW_DAYS
+    pub daily_volume: [u64; VOLUME_WINDOW_DAYS],
+    /// Referrer the user's trades are credited to, default when the user was never referred
+    pub referrer: Pubkey,
+    pub referral_bound_at: i64,
+}
+
+impl UserStats {
+    pub const PREFIX_SEED: &'static [u8] = b"user-stats";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+
+    /// Returns the volume traded during the last `VOLUME_WINDOW_DAYS` days, today included
+    pub fn window_volume(&self, timestamp: i64) -> u64 {
+        let today = timestamp / SECONDS_IN_A_DAY;
+        let first_day = today - VOLUME_WINDOW_DAYS as i64 + 1;
+        (first_day.max(self.last_trade_day - VOLUME_WINDOW_DAYS as i64 + 1)..=self.last_trade_day.min(today))
+            .map(|day| self.daily_volume[day as usize % VOLUME_WINDOW_DAYS])
+            .sum()
+    }
+
+    pub fn record_volume(&mut self, volume: u64, timestamp: i64) {
+        let today = timestamp / SECONDS_IN_A_DAY;
+        if today - self.last_trade_day >= VOLUME_WINDOW_DAYS as i64 {
+            self.daily_volume = [0; VOLUME_WINDOW_DAYS];
+        } else {
+            // clear the slots of the days without trades since the last one
+            for day in (self.last_trade_day + 1)..=today {
+                self.daily_volume[day as usize % VOLUME_WINDOW_DAYS] = 0;
+            }
+        }
+        self.daily_volume[today as usize % VOLUME_WINDOW_DAYS] += volume;
+        self.last_trade_day = self.last_trade_day.max(today);
+        self.total_volume += volume;
+    }
+}
+
+/// Referral statistics of a registered referrer.
+/// Rewards are held by the vault until the referrer claims them.
+#[account]
+#[derive(InitSpace)]
+pub struct Referrer {
+    pub authority: Pubkey,
+    pub referred_volume: u64,
+    pub total_earned: u64,
+    pub pending_rewards: u64,
+    pub referee_count: u64,
+}
+
+impl Referrer {
+    pub const PREFIX_SEED: &'static [u8] = b"referrer";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Maps a human readable code to a referrer, seeded by the normalized code
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralCode {
+    pub referrer: Pubkey,
+    /// Normalized code, zero padded
+    pub code: [u8; MAX_REFERRAL_CODE_LEN],
+}
+
+impl ReferralCode {
+    pub const PREFIX_SEED: &'static [u8] = b"referral-code";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+
+    /// Lowercases the code and checks its length and characters (a-z, 0-9, '-' and '_')
+    pub fn normalize(code: &str) -> Result<String> {
+        let code = code.to_ascii_lowercase();
+        require!(
+            (MIN_REFERRAL_CODE_LEN..=MAX_REFERRAL_CODE_LEN).contains(&code.len())
+                && code.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-' || c == b'_'),
+            MushiProgramError::InvalidReferralCode
+        );
+        Ok(code)
+    }
+
+    /// Returns the normalized code without its padding
+    pub fn code_bytes(&self) -> &[u8] {
+        let len = self.code.iter().position(|&c| c == 0).unwrap_or(MAX_REFERRAL_CODE_LEN);
+        &self.code[..len]
+    }
+}
+
+/// Configuration change queued by the admin, executable by anyone once `eta` is reached
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfigChange {
+    /// Receives the rent back once the change is executed or cancelled
+    pub proposer: Pubkey,
+    pub eta: i64,
+    pub change: UpdateMainStateInput,
+}
+
+impl PendingConfigChange {
+    pub const PREFIX_SEED: &'static [u8] = b"pending-config";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    /// Non fee settings of `update_main_state` and config changes
+    ConfigManager,
+    /// Fee settings and fee recipients
+    FeeManager,
+    /// Can pause and unpause
+    Pauser,
+    /// Can claim the protocol fees
+    TreasuryClaimer,
+    /// Can call `start`
+    LaunchOperator,
+}
+
+/// Keys holding the delegated roles, default when a role is not granted.
+/// The admins can always act in place of any role.
+#[account]
+#[derive(InitSpace)]
+pub struct Roles {
+    pub config_manager: Pubkey,
+    pub fee_manager: Pubkey,
+    pub pauser: Pubkey,
+    pub treasury_claimer: Pubkey,
+    pub launch_operator: Pubkey,
+}
+
+impl Roles {
+    pub const PREFIX_SEED: &'static [u8] = b"roles";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+
+    pub fn holder_mut(&mut self, role: Role) -> &mut Pubkey {
+        match role {
+            Role::ConfigManager => &mut self.config_manager,
+            Role::FeeManager => &mut self.fee_manager,
+            Role::Pauser => &mut self.pauser,
+            Role::TreasuryClaimer => &mut self.treasury_claimer,
+            Role::LaunchOperator => &mut self.launch_operator,
+        }
+    }
+
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        let holder = match role {
+            Role::ConfigManager => self.config_manager,
+            Role::FeeManager => self.fee_manager,
+            Role::Pauser => self.pauser,
+            Role::TreasuryClaimer => self.treasury_claimer,
+            Role::LaunchOperator => self.launch_operator,
+        };
+        *key != Pubkey::default() && holder == *key
+    }
+}
+
+/// Team allocation minted at launch and held by the vesting account's token account.
+/// Nothing is released before `cliff_ts`, then it vests linearly from `start_ts` to `end_ts`.
+#[account]
+#[derive(InitSpace)]
+pub struct TeamVesting {
+    pub beneficiary: Pubkey,
+    pub total: u64,
+    pub claimed: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+}
+
+impl TeamVesting {
+    pub const PREFIX_SEED: &'static [u8] = b"team-vesting";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+
+    /// Returns the amount vested at `timestamp`, claimed part included
+    pub fn vested(&self, timestamp: i64) -> u64 {
+        if timestamp < self.cliff_ts {
+            0
+        } else if timestamp >= self.end_ts {
+            self.total
+        } else {
+            (self.total as u128 * (timestamp - self.start_ts) as u128 / (self.end_ts - self.start_ts) as u128) as u64
+        }
+    }
+}
+
+/// Per wallet record of the purchases limited around the launch
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchBuyer {
+    /// Lamports spent on the wallet's single anti-bot window buy
+    pub anti_bot_bought: u64,
+    /// Lamports spent during the presale
+    pub presale_bought: u64,
+    pub version: u8,
+    /// Room for new fields, zeroed
+    pub reserved: [u8; 32],
+}
+
+impl LaunchBuyer {
+    pub const PREFIX_SEED: &'static [u8] = b"launch-buyer";
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Fee overrides applied between `start_ts` and `end_ts`.
+/// Overrides only ever lower the regular fees.
+#[account]
+#[derive(InitSpace)]
+pub struct Promotion {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub buy_treasury_bps: u64,
+    pub sell_treasury_bps: u64,
+    /// Part of the regular interest fee charged, in basis points
+    pub interest_fee_bps: u64,
+    pub started_announced: bool,
+    pub ended_announced: bool,
+}
+
+impl Promotion {
+    pub const PREFIX_SEED: &'static [u8] = b"promotion";
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
+
+    pub fn is_active(&self, timestamp: i64) -> bool {
+        self.start_ts <= timestamp && timestamp < self.end_ts
+    }
+
+    /// Emits the start and end events once each, on the first call after they are due
+    pub fn announce(&mut self, timestamp: i64) {
+        if self.end_ts == 0 {
+            return;
+        }
+        if !self.started_announced && timestamp >= self.start_ts {
+            self.started_announced = true;
+            emit!(PromotionStarted {
+                start_ts: self.start_ts,
+                end_ts: self.end_ts,
+            });
+        }
+        if !self.ended_announced && timestamp >= self.end_ts {
+            self.ended_announced = true;
+            emit!(PromotionEnded {
+                start_ts: self.start_ts,
+                end_ts: self.end_ts,
+            });
+        }
+    }
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct UserLoan {
     pub collateral: u64,
     pub borrowed: u64,
     pub end_date: i64,
     pub number_of_days: u64,
+    pub version: u8,
+    pub reserved: [u8; 32],
 }
 
 impl UserLoan {
     pub const PREFIX_SEED: &'static [u8] = b"user_loan";
-    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct DailyStats {
     pub date: i64,
     pub borrowed: u64,
     pub collateral: u64,
+    pub version: u8,
+    pub reserved: [u8; 32],
 }
 
 impl DailyStats {
     pub const PREFIX_SEED: &'static [u8] = b"daily_stats";
-    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct GlobalStats {
     pub started: bool,
     pub last_liquidation_date: i64,
@@ -55,11 +648,29 @@ pub struct GlobalStats {
     pub token_supply: u64,
     pub last_price: u64,
     pub token: Pubkey,
+    /// Protocol fees held by the vault until they are claimed, not part of the backing
+    pub accrued_fees: u64,
+    /// SOL held by the vault that backs the token, excluding the loaned out part
+    pub backing: u64,
+    /// Referral rewards held by the vault until they are claimed, not part of the backing
+    pub referral_rewards: u64,
+    pub version: u8,
+    /// When `execute_relaunch` becomes callable, 0 when no relaunch is scheduled
+    pub relaunch_eta: i64,
+    /// Buys are refused before this time
+    pub trading_start_ts: i64,
+    /// Until this time each wallet can buy once, for at most `anti_bot_max_buy` lamports
+    pub anti_bot_end_ts: i64,
+    pub anti_bot_max_buy: u64,
+    /// Room for new fields, zeroed
+    pub reserved: [u8; 32],
 }
 
 impl GlobalStats {
     pub const PREFIX_SEED: &'static [u8] = b"global_stats";
-    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();
+    /// 1: versioned layout, 2: `relaunch_eta`, 3: trading start and anti-bot window
+    pub const VERSION: u8 = 3;
+    pub const MAX_SIZE: usize = Self::INIT_SPACE;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
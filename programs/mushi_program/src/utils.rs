@@ -1,6 +1,7 @@
 use anchor_lang::{
     prelude::*,
     solana_program::{
+        keccak::hashv,
         program::{invoke, invoke_signed},
         system_instruction::transfer,
     },
@@ -8,7 +9,7 @@ use anchor_lang::{
 use anchor_spl::token::{self, Burn, MintTo, Token, TokenAccount, Transfer};
 use crate::state::DailyStats;
 use crate::{
-    constants::{FEES_BUY, SECONDS_IN_A_DAY, VAULT_SEED, LAMPORTS_PER_SOL}, 
+    constants::{FEE_BASE_10000, SECONDS_IN_A_DAY, VAULT_SEED, LAMPORTS_PER_SOL, PAUSE_LIQUIDATION}, 
     state::{MainState, GlobalStats},
     error::MushiProgramError,
 };
@@ -99,6 +100,37 @@ pub fn transfer_sol<'info>(
     Ok(())
 }
 
+/// Grows a program owned account to `new_len` bytes, zero filled,
+/// with `payer` topping it up to rent exemption
+pub fn realloc_account<'info>(
+    account: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+    let rent = Rent::get()?.minimum_balance(new_len).saturating_sub(account.lamports());
+    if rent > 0 {
+        transfer_sol(payer, account.clone(), system_program, rent, None)?;
+    }
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Checks `leaf` against `root`, the proof nodes being hashed in sorted pairs
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).0
+        } else {
+            hashv(&[sibling, &node]).0
+        }
+    });
+    computed == root
+}
+
 pub fn trasnfer_sol_to_pubkey<'info>(
     sender: AccountInfo<'info>,
     receiver: &Pubkey,
@@ -110,7 +142,10 @@ pub fn trasnfer_sol_to_pubkey<'info>(
     Ok(())
 }
 
+/// Does nothing while liquidations are paused, they catch up once unpaused
+#[allow(clippy::too_many_arguments)]
 pub fn liquidate<'info>(
+    main_state: &MainState,
     last_liquidation_date_state: &mut DailyStats,
     global_state: &mut GlobalStats,
     token_vault: AccountInfo<'info>,
@@ -119,6 +154,9 @@ pub fn liquidate<'info>(
     token_program: AccountInfo<'info>,
     vault_owner_bump: u8,
 ) -> Result<()> {
+    if main_state.is_paused(PAUSE_LIQUIDATION) {
+        return Ok(());
+    }
     let mut borrowed: u64 = 0;
     let mut collateral: u64 = 0;
     let mut last_liquidation_date = global_state.last_liquidation_date;
@@ -175,6 +213,11 @@ pub fn get_date_from_timestamp(timestamp: i64) -> i64 {
     timestamp - (timestamp % SECONDS_IN_A_DAY)
 }
 
+/// Returns `bps` basis points of `amount`
+pub fn get_bps_fee(amount: u64, bps: u64) -> u64 {
+    ((amount as u128 * bps as u128) / FEE_BASE_10000 as u128) as u64
+}
+
 pub fn get_interest_fee(amount: u64, number_of_days: u64) -> u64 {
     // Daily interest rate of 3.9% (0.039) plus base fee of 0.1% (0.001)
     // Using 1e9 as precision factor since we're working with u64 instead of u256
@@ -243,6 +286,10 @@ fn is_leap_year(year: i64) -> bool {
 }
 
 pub fn add_loans_by_date(global_state: &mut Box<Account<GlobalStats>>, daily_state: &mut Box<Account<DailyStats>>, borrowed: u64, collateral: u64) -> Result<()> {
+    // the end date account may have just been created
+    if daily_state.version == 0 {
+        daily_state.version = DailyStats::VERSION;
+    }
     daily_state.borrowed += borrowed;
     daily_state.collateral += collateral;
     global_state.total_borrowed += borrowed;
@@ -0,0 +1,78 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+// launches its own token, so it needs a validator where `start` has not run yet
+describe("mushi_program_anti_bot", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const antiBotMaxBuy = 0.05;
+  let tradingStartTs: number;
+
+  before(async () => {
+    if (!(await connectivity.getMainStateInfo())) {
+      const initRes = await connectivity.initialize();
+      if (!initRes.isPass) throw "Failed to init main state";
+      await sleep(5_000);
+    }
+    const globalInfo = await connectivity.getGlobalInfo();
+    if (!globalInfo) throw "Failed to get global info";
+    if (globalInfo.started) throw "Already launched, run on a validator where start has not run";
+
+    tradingStartTs = Math.floor(Date.now() / 1000) + 30;
+    const startRes = await connectivity.start({
+      tokenName: "Mushi",
+      tokenSymbol: "MUSHI",
+      tokenUri: "",
+      solAmount: 1,
+      tradingStartTs,
+      antiBotDuration: 600,
+      antiBotMaxBuy,
+    });
+    if (!startRes.isPass) throw "Failed to start the launch";
+    await sleep(5_000);
+
+    const info = await connectivity.getGlobalInfo();
+    if (!info) throw "Failed to get global info";
+    log({ tradingStartTs: info.tradingStartTs, antiBotEndTs: info.antiBotEndTs, antiBotMaxBuy: info.antiBotMaxBuy });
+    if (info.tradingStartTs !== tradingStartTs) throw "Trading start was not scheduled";
+  });
+
+  it("Buys before the trading start fail", async () => {
+    const buyRes = await connectivity.buy(0.01);
+    if (buyRes.isPass) throw "Bought before the trading start";
+    if (connectivity.lastErrorCode !== "TradingNotStarted") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  it("Buys over the anti-bot limit fail", async () => {
+    // leave some slack for the validator clock
+    await sleep(Math.max(0, tradingStartTs * 1000 - Date.now()) + 5_000);
+    const buyRes = await connectivity.buy(antiBotMaxBuy * 2);
+    if (buyRes.isPass) throw "Bought over the anti-bot limit";
+    if (connectivity.lastErrorCode !== "AntiBotLimitExceeded") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  it("One buy up to the limit goes through", async () => {
+    const buyRes = await connectivity.buy(antiBotMaxBuy);
+    if (!buyRes.isPass) throw "Failed to buy up to the anti-bot limit";
+    await sleep(5_000);
+  });
+
+  it("A second buy during the window fails", async () => {
+    const buyRes = await connectivity.buy(0.01);
+    if (buyRes.isPass) throw "Bought twice during the anti-bot window";
+    if (connectivity.lastErrorCode !== "AntiBotLimitExceeded") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+});
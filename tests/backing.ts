@@ -0,0 +1,82 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { GlobalStateInfo, sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_backing", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const vaultOwner = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("vault")],
+    programId
+  )[0];
+  let globalInfo: GlobalStateInfo | null = null;
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+
+  it("Get initial state info", async () => {
+    globalInfo = await connectivity.getGlobalInfo();
+    if (!globalInfo) throw "Failed to get global state info";
+    log(`Backing: ${globalInfo.backing} lamports`);
+  });
+
+  it("Donate to backing", async () => {
+    if (!globalInfo) throw "Failed to get global state info";
+    const donation = 0.01 * web3.LAMPORTS_PER_SOL;
+    const donateRes = await connectivity.donateToBacking(donation);
+    if (!donateRes.isPass) throw "Failed to donate to backing";
+    log({ donateRes: donateRes.info });
+
+    await sleep(5_000);
+    const info = await connectivity.getGlobalInfo();
+    if (!info) throw "Failed to get global state info";
+    log(`Backing after donation: ${info.backing} lamports`);
+    if (info.backing - globalInfo.backing !== donation) throw "Backing did not grow by the donation";
+    globalInfo = info;
+  });
+
+  it("Sync stray lamports into backing", async () => {
+    if (!globalInfo) throw "Failed to get global state info";
+    const stray = 0.01 * web3.LAMPORTS_PER_SOL;
+    // a plain transfer to the vault does not move the price until it is synced
+    const tx = new web3.Transaction().add(
+      web3.SystemProgram.transfer({
+        fromPubkey: provider.publicKey,
+        toPubkey: vaultOwner,
+        lamports: stray,
+      })
+    );
+    await provider.sendAndConfirm(tx);
+
+    const beforeSync = await connectivity.getGlobalInfo();
+    if (!beforeSync) throw "Failed to get global state info";
+    if (beforeSync.backing !== globalInfo.backing) throw "Stray lamports changed the backing";
+    if (beforeSync.lastPrice !== globalInfo.lastPrice) throw "Stray lamports moved the price";
+
+    // everything in the vault above rent, backing, fees and rewards is stray
+    const vaultLamports = await connection.getBalance(vaultOwner);
+    const rent = await connection.getMinimumBalanceForRentExemption(0);
+    const expectedStray =
+      vaultLamports - rent - beforeSync.backing - beforeSync.accruedFees - beforeSync.referralRewards;
+    if (expectedStray < stray) throw "Transfer did not reach the vault";
+
+    const syncRes = await connectivity.syncBacking();
+    if (!syncRes.isPass) throw "Failed to sync backing";
+    log({ syncRes: syncRes.info });
+
+    await sleep(5_000);
+    const info = await connectivity.getGlobalInfo();
+    if (!info) throw "Failed to get global state info";
+    log(`Backing after sync: ${info.backing} lamports`);
+    if (info.backing - beforeSync.backing !== expectedStray) throw "Backing did not grow by the stray lamports";
+  });
+});
@@ -22,6 +22,7 @@ describe("mushi_program_buy_with_referral", () => {
     programId,
   });
   const user = provider.publicKey;
+  // must be registered as a referrer, see referrer.ts
   const referralPubkey = new web3.PublicKey("HxEx3porEpbGa3PvmocLqooc6VPAAULkYxcr7vSm2hAn");
 
   // Parameters for the buy operation
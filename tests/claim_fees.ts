@@ -0,0 +1,81 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { MainStateInfo, GlobalStateInfo, sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_claim_fees", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  let mainStateInfo: MainStateInfo | null = null;
+  let globalInfo: GlobalStateInfo | null = null;
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+
+  it("Get initial state info", async () => {
+    mainStateInfo = await connectivity.getMainStateInfo();
+    if (!mainStateInfo) throw "Failed to get main state info";
+    log({ feeRecipients: mainStateInfo.feeRecipients });
+
+    globalInfo = await connectivity.getGlobalInfo();
+    if (!globalInfo) throw "Failed to get global state info";
+    log(`Accrued fees: ${globalInfo.accruedFees} lamports`);
+  });
+
+  it("Claim fees", async () => {
+    if (!mainStateInfo || !globalInfo) throw "State info is not available";
+    if (globalInfo.accruedFees === 0) {
+      // nothing to claim yet, a buy accrues its treasury fee
+      const buyRes = await connectivity.buy(0.1);
+      if (!buyRes.isPass) throw "Failed to buy";
+      await sleep(5_000);
+    }
+    const recipients = mainStateInfo.feeRecipients;
+    const before = await connectivity.getGlobalInfo();
+    if (!before) throw "Failed to get global state info";
+    const accruedFees = before.accruedFees;
+    const balancesBefore = await Promise.all(
+      recipients.map((recipient) =>
+        connection.getBalance(recipient.address)
+      )
+    );
+
+    const claimRes = await connectivity.claimFees();
+    if (!claimRes.isPass) throw "Failed to claim fees";
+    log({ claimRes: claimRes.info });
+
+    await sleep(5_000);
+
+    const balancesAfter = await Promise.all(
+      recipients.map((recipient) =>
+        connection.getBalance(recipient.address)
+      )
+    );
+    // each recipient gets its weight of the accrued fees, the last one also gets the rounding dust
+    let remaining = accruedFees;
+    recipients.forEach((recipient, i) => {
+      const expected = i === recipients.length - 1
+        ? remaining
+        : Math.floor((accruedFees * recipient.weightBps) / 10_000);
+      remaining -= expected;
+      const received = balancesAfter[i] - balancesBefore[i];
+      log(`${recipient.address.toBase58()} received ${received} lamports, expected ${expected}`);
+      // the claimer also paid the transaction fee
+      const txFee = recipient.address.equals(provider.publicKey) ? 10_000 : 0;
+      if (received > expected || received < expected - txFee)
+        throw `${recipient.address.toBase58()} received ${received} instead of ${expected}`;
+    });
+
+    const after = await connectivity.getGlobalInfo();
+    if (!after) throw "Failed to get global state info";
+    if (after.accruedFees !== 0) throw `Accrued fees left after the claim: ${after.accruedFees}`;
+  });
+});
@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_config_change", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+
+  // short enough to wait out in the test
+  const configTimelock = 30;
+  let originalFlashCloseFee: number;
+  let flashCloseFee: number;
+
+  const waitForEta = async () => {
+    const pending = await connectivity.getPendingConfigChange();
+    if (!pending) throw "Config change is not pending";
+    await sleep(Math.max(pending.eta * 1000 - Date.now(), 0) + 5_000);
+  };
+
+  before(async () => {
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    if (info.configTimelock !== 0) throw "Run with no config timelock, a longer one cannot be waited out";
+    originalFlashCloseFee = info.flashCloseFee;
+    // one step stays within the daily change limit
+    flashCloseFee = info.flashCloseFee < 25 ? info.flashCloseFee + 1 : info.flashCloseFee - 1;
+    const updateRes = await connectivity.updateMainState({ configTimelock });
+    if (!updateRes.isPass) throw "Failed to set the config timelock";
+    await sleep(5_000);
+  });
+
+  it("Direct updates are rejected once a timelock is set", async () => {
+    const updateRes = await connectivity.updateMainState({ flashCloseFee });
+    if (updateRes.isPass) throw "Update bypassed the config timelock";
+    if (connectivity.lastErrorCode !== "ConfigTimelocked") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  it("Propose a config change", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const proposeRes = await connectivity.proposeConfigChange({ flashCloseFee });
+    if (!proposeRes.isPass) throw "Failed to propose config change";
+    log({ proposeRes: proposeRes.info });
+    await sleep(5_000);
+
+    const pending = await connectivity.getPendingConfigChange();
+    if (!pending) throw "Config change is not pending";
+    log(`Config change executable at ${new Date(pending.eta * 1000).toISOString()}`);
+    if (pending.eta < now + configTimelock - 5) throw `Eta ${pending.eta} is before the timelock`;
+  });
+
+  it("Executing before the eta is rejected", async () => {
+    const executeRes = await connectivity.executeConfigChange();
+    if (executeRes.isPass) throw "Config change executed before its eta";
+    if (connectivity.lastErrorCode !== "ConfigChangeNotReady") throw `Unexpected error ${connectivity.lastErrorCode}`;
+    if (!(await connectivity.getPendingConfigChange())) throw "Config change is no longer pending";
+  });
+
+  it("Execute the config change once the timelock has elapsed", async () => {
+    await waitForEta();
+    const executeRes = await connectivity.executeConfigChange();
+    if (!executeRes.isPass) throw "Failed to execute config change";
+    await sleep(5_000);
+
+    const mainStateInfo = await connectivity.getMainStateInfo();
+    log({ flashCloseFee: mainStateInfo?.flashCloseFee });
+    if (mainStateInfo?.flashCloseFee !== flashCloseFee) throw "Config change was not applied";
+    if (await connectivity.getPendingConfigChange()) throw "Executed config change is still pending";
+  });
+
+  after(async () => {
+    // the restore goes through the timelock as well
+    if (await connectivity.getPendingConfigChange()) await connectivity.cancelConfigChange();
+    await sleep(5_000);
+    const proposeRes = await connectivity.proposeConfigChange({ flashCloseFee: originalFlashCloseFee, configTimelock: 0 });
+    if (!proposeRes.isPass) throw "Failed to propose the restore";
+    await sleep(5_000);
+    await waitForEta();
+    const executeRes = await connectivity.executeConfigChange();
+    if (!executeRes.isPass) throw "Failed to restore the config";
+  });
+});
@@ -0,0 +1,96 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { MushiProgramRpc } from "./mushiProgramRpc";
+
+const SECONDS_IN_A_DAY = 86400;
+const MAX_REFERRAL_BINDING_DURATION = 365 * SECONDS_IN_A_DAY;
+const MAX_CONFIG_TIMELOCK = 30 * SECONDS_IN_A_DAY;
+
+describe("mushi_program_config_validation", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const vaultOwner = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("vault")],
+    programId
+  )[0];
+  const mainState = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("main_state")],
+    programId
+  )[0];
+  describe("init", () => {
+    // an existing main state makes every init fail, which would prove nothing
+    before(async function () {
+      if (await connectivity.getMainStateInfo()) this.skip();
+    });
+
+    it("Init with the vault as fee recipient fails", async () => {
+      const initRes = await connectivity.initialize({
+        feeRecipients: [{ address: vaultOwner, weightBps: 10_000 }],
+      });
+      if (initRes.isPass) throw "Init accepted the vault as fee recipient";
+    });
+
+    it("Init with the main state as fee recipient fails", async () => {
+      const initRes = await connectivity.initialize({
+        feeRecipients: [
+          { address: web3.Keypair.generate().publicKey, weightBps: 5_000 },
+          { address: mainState, weightBps: 5_000 },
+        ],
+      });
+      if (initRes.isPass) throw "Init accepted the main state as fee recipient";
+    });
+  });
+
+  describe("update", () => {
+    before(async function () {
+      const info = await connectivity.getMainStateInfo();
+      if (!info) this.skip();
+    });
+
+    it("Updating to the current fee recipients passes", async () => {
+      const info = await connectivity.getMainStateInfo();
+      if (!info) throw "Failed to get main state info";
+      const updateRes = await connectivity.updateMainState({ feeRecipients: info.feeRecipients });
+      if (!updateRes.isPass) throw "Valid update failed";
+    });
+
+    it("Update with the vault as fee recipient fails", async () => {
+      const updateRes = await connectivity.updateMainState({
+        feeRecipients: [{ address: vaultOwner, weightBps: 10_000 }],
+      });
+      if (updateRes.isPass) throw "Update accepted the vault as fee recipient";
+    });
+
+    it("Update with the main state as fee recipient fails", async () => {
+      const updateRes = await connectivity.updateMainState({
+        feeRecipients: [{ address: mainState, weightBps: 10_000 }],
+      });
+      if (updateRes.isPass) throw "Update accepted the main state as fee recipient";
+    });
+
+    it("Update with a referral binding duration over a year fails", async () => {
+      const updateRes = await connectivity.updateMainState({
+        referralBindingDuration: MAX_REFERRAL_BINDING_DURATION + 1,
+      });
+      if (updateRes.isPass) throw "Update accepted a referral binding duration over the maximum";
+    });
+
+    it("Update with a config timelock over 30 days fails", async () => {
+      const updateRes = await connectivity.updateMainState({
+        configTimelock: MAX_CONFIG_TIMELOCK + 1,
+      });
+      if (updateRes.isPass) throw "Update accepted a config timelock over the maximum";
+    });
+  });
+});
@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc, MainStateInfo } from "./mushiProgramRpc";
+
+const log = console.log;
+const SECONDS_IN_A_DAY = 86400;
+const MIN_FLASH_CLOSE_FEE = 5;
+const MAX_FLASH_CLOSE_FEE = 25;
+
+describe("mushi_program_fee_limits", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  let baseline: number;
+  let limit: number;
+  let direction: number;
+
+  const getMainStateInfo = async (): Promise<MainStateInfo> => {
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    return info;
+  };
+
+  before(async () => {
+    const info = await getMainStateInfo();
+    const today = Math.floor(Date.now() / 1000 / SECONDS_IN_A_DAY);
+    // changes are measured from the fees at the start of the day
+    baseline = info.feeBaseline.day === today ? info.feeBaseline.flashCloseFee : info.flashCloseFee;
+    limit = info.feeChangeLimits.flashCloseFee;
+    direction = baseline + limit + 1 <= MAX_FLASH_CLOSE_FEE ? 1 : -1;
+    if (baseline + (limit + 1) * direction < MIN_FLASH_CLOSE_FEE) throw "Flash close fee range too narrow for the test";
+    log(`Flash close fee baseline: ${baseline}, daily limit: ${limit}`);
+  });
+
+  it("Move a fee up to the daily limit", async () => {
+    const flashCloseFee = baseline + limit * direction;
+    const updateRes = await connectivity.updateMainState({ flashCloseFee });
+    if (!updateRes.isPass) throw "Failed to change the fee within the limit";
+    await sleep(5_000);
+
+    const info = await getMainStateInfo();
+    if (info.flashCloseFee !== flashCloseFee) throw "Fee was not updated";
+  });
+
+  it("A second change on the same day cannot go past the limit", async () => {
+    const updateRes = await connectivity.updateMainState({
+      flashCloseFee: baseline + (limit + 1) * direction,
+    });
+    if (updateRes.isPass) throw "Fee moved past the daily limit with a second change";
+  });
+
+  it("Changes back towards the baseline are allowed", async () => {
+    const updateRes = await connectivity.updateMainState({ flashCloseFee: baseline });
+    if (!updateRes.isPass) throw "Failed to move the fee back to its baseline";
+    await sleep(5_000);
+
+    const info = await getMainStateInfo();
+    if (info.flashCloseFee !== baseline) throw "Fee was not restored";
+  });
+
+  it("Limits above the hard caps are rejected", async () => {
+    const info = await getMainStateInfo();
+    const updateRes = await connectivity.updateMainState({
+      feeChangeLimits: { ...info.feeChangeLimits, tradeFeeBps: 201 },
+    });
+    if (updateRes.isPass) throw "Fee change limit above its hard cap was accepted";
+  });
+
+  it("Raised limits only apply to later updates", async () => {
+    const info = await getMainStateInfo();
+    const raised = { ...info.feeChangeLimits, flashCloseFee: limit + 1 };
+    // raising the limit and using it in the same update is refused
+    const sameUpdateRes = await connectivity.updateMainState({
+      feeChangeLimits: raised,
+      flashCloseFee: baseline + (limit + 1) * direction,
+    });
+    if (sameUpdateRes.isPass) throw "Raised limit applied to its own update";
+
+    const raiseRes = await connectivity.updateMainState({ feeChangeLimits: raised });
+    if (!raiseRes.isPass) throw "Failed to raise the limit";
+    await sleep(5_000);
+    const updateRes = await connectivity.updateMainState({
+      flashCloseFee: baseline + (limit + 1) * direction,
+    });
+    if (!updateRes.isPass) throw "Raised limit was not applied";
+    await sleep(5_000);
+
+    const restoreRes = await connectivity.updateMainState({
+      flashCloseFee: baseline,
+      feeChangeLimits: info.feeChangeLimits,
+    });
+    if (!restoreRes.isPass) throw "Failed to restore the fee and limits";
+  });
+});
@@ -0,0 +1,87 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, fundedKeypair, MushiProgramRpc, FeeScheduleInfo } from "./mushiProgramRpc";
+
+const log = console.log;
+// assumes no promotion is running and no fee tier applies to a new user
+describe("mushi_program_fee_schedule", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  let schedule: FeeScheduleInfo;
+
+  before(async () => {
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    schedule = info.feeSchedule;
+  });
+
+  const expectRejected = async (change: Partial<FeeScheduleInfo>, errorCode: string) => {
+    const updateRes = await connectivity.updateMainState({ feeSchedule: { ...schedule, ...change } });
+    if (updateRes.isPass) throw `Fee schedule ${JSON.stringify(change)} was accepted`;
+    if (connectivity.lastErrorCode !== errorCode) throw `Unexpected error ${connectivity.lastErrorCode}`;
+  };
+
+  it("A treasury fee over 2.5% is rejected", async () => {
+    await expectRejected({ buyTreasuryBps: 251 }, "InvalidBuyFee");
+  });
+
+  it("A referral fee over 1% is rejected", async () => {
+    await expectRejected({ sellReferralBps: 101 }, "InvalidSellFee");
+  });
+
+  it("A backing fee outside 0.8% to 2.5% is rejected", async () => {
+    await expectRejected({ buyBackingBps: 79 }, "InvalidBuyFee");
+    await expectRejected({ sellBackingBps: 251 }, "InvalidSellFee");
+  });
+
+  it("A side totalling over 5% is rejected", async () => {
+    // each fee is within its own cap
+    await expectRejected({ buyTreasuryBps: 250, buyReferralBps: 100, buyBackingBps: 200 }, "InvalidBuyFee");
+  });
+
+  it("A referred buy splits the SOL between treasury, referrer and backing", async () => {
+    if (!(await connectivity.getReferrerInfo(provider.publicKey))) {
+      const registerRes = await connectivity.registerReferrer();
+      if (!registerRes.isPass) throw "Failed to register referrer";
+      await sleep(5_000);
+    }
+    const user = await fundedKeypair(connection, 2);
+    const userRpc = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(user), programId });
+    const solAmount = 0.5;
+    const lamports = solAmount * web3.LAMPORTS_PER_SOL;
+
+    const before = await connectivity.getGlobalInfo();
+    const referrerBefore = await connectivity.getReferrerInfo(provider.publicKey);
+    if (!before || !referrerBefore) throw "Failed to get state info";
+    const buyRes = await userRpc.buy_with_referral(solAmount, provider.publicKey);
+    if (!buyRes.isPass) throw "Failed to buy with referral";
+    await sleep(5_000);
+
+    const after = await connectivity.getGlobalInfo();
+    const referrerAfter = await connectivity.getReferrerInfo(provider.publicKey);
+    if (!after || !referrerAfter) throw "Failed to get state info";
+    const treasury = Math.floor((lamports * schedule.buyTreasuryBps) / 10_000);
+    const referral = Math.floor((lamports * schedule.buyReferralBps) / 10_000);
+    const split = {
+      treasury: after.accruedFees - before.accruedFees,
+      referral: after.referralRewards - before.referralRewards,
+      backing: after.backing - before.backing,
+    };
+    log({ split, treasury, referral });
+    if (split.treasury !== treasury) throw `Treasury got ${split.treasury} instead of ${treasury}`;
+    if (split.referral !== referral) throw `Referral rewards got ${split.referral} instead of ${referral}`;
+    if (referrerAfter.pendingRewards - referrerBefore.pendingRewards !== referral) throw "Referrer was not credited";
+    if (split.backing !== lamports - treasury - referral) throw `Backing got ${split.backing}`;
+  });
+});
@@ -22,7 +22,6 @@ describe("mushi_program", () => {
   });
   const user = provider.publicKey;
 
-    const feeReceiver = new web3.PublicKey("8CHNnNzHme7hVv2Qw2WHbxX54EWJ6NMkjJ1zRTEkNvsg");
   const tokenAmount = 100_000;
   const tokenName = "mushi";
   const tokenSymbol = "MUSHI";
@@ -31,12 +30,7 @@ describe("mushi_program", () => {
   it("init", async () => {
     const info = await connectivity.getMainStateInfo();
     if (!info) {
-      const initRes = await connectivity.initialize({
-        sellFee: 975,
-        buyFee: 975,
-        buyFeeLeverage: 10,
-        feeReceiver,
-      });
+      const initRes = await connectivity.initialize();
       log({ initRes: initRes.info });
       if (!initRes.isPass) throw "failed to init mainstate";
       await sleep(15_000);
@@ -0,0 +1,67 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, fundedKeypair, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_integrator_fee", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const maxIntegratorFeeBps = 50;
+  const solAmount = 0.5;
+  let originalMax: number;
+  let integrator: web3.PublicKey;
+  let userRpc: MushiProgramRpc;
+
+  before(async () => {
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    originalMax = info.maxIntegratorFeeBps;
+    const updateRes = await connectivity.updateMainState({ maxIntegratorFeeBps });
+    if (!updateRes.isPass) throw "Failed to set the maximum integrator fee";
+    await sleep(5_000);
+
+    // funded so that a small fee does not leave it below the rent exemption
+    integrator = (await fundedKeypair(connection, 1)).publicKey;
+    const user = await fundedKeypair(connection, 2);
+    userRpc = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(user), programId });
+  });
+
+  it("A buy pays the integrator its fee", async () => {
+    const before = await connection.getBalance(integrator);
+    const buyRes = await userRpc.buy(solAmount, false, { address: integrator, feeBps: maxIntegratorFeeBps });
+    if (!buyRes.isPass) throw "Failed to buy with an integrator fee";
+    await sleep(5_000);
+
+    const paid = (await connection.getBalance(integrator)) - before;
+    const expected = Math.floor((solAmount * web3.LAMPORTS_PER_SOL * maxIntegratorFeeBps) / 10_000);
+    log({ paid, expected });
+    if (paid !== expected) throw `Integrator got ${paid} instead of ${expected}`;
+  });
+
+  it("A fee over the configured maximum is rejected", async () => {
+    const buyRes = await userRpc.buy(solAmount, false, { address: integrator, feeBps: maxIntegratorFeeBps + 1 });
+    if (buyRes.isPass) throw "Integrator fee over the maximum was accepted";
+    if (userRpc.lastErrorCode !== "InvalidIntegratorFee") throw `Unexpected error ${userRpc.lastErrorCode}`;
+  });
+
+  it("A fee without the integrator account is rejected", async () => {
+    const buyRes = await userRpc.buy(solAmount, false, { address: null, feeBps: maxIntegratorFeeBps });
+    if (buyRes.isPass) throw "Integrator fee was accepted without its account";
+    if (userRpc.lastErrorCode !== "IntegratorNotFound") throw `Unexpected error ${userRpc.lastErrorCode}`;
+  });
+
+  after(async () => {
+    await connectivity.updateMainState({ maxIntegratorFeeBps: originalMax });
+  });
+});
@@ -0,0 +1,55 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_migrate", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+
+  it("Migrate the main and global state", async () => {
+    const mainStateRes = await connectivity.migrateMainState();
+    if (!mainStateRes.isPass) throw "Failed to migrate main state";
+    const globalStateRes = await connectivity.migrateGlobalState();
+    if (!globalStateRes.isPass) throw "Failed to migrate global state";
+    await sleep(5_000);
+
+    const mainStateInfo = await connectivity.getMainStateInfo();
+    if (!mainStateInfo) throw "Failed to get main state info";
+    log({ mainStateInfo });
+    // the fee change limits added by v3 must not be left zeroed
+    if (mainStateInfo.feeChangeLimits.flashCloseFee === 0) throw "Fee change limits were not set";
+
+    const againRes = await connectivity.migrateMainState();
+    if (!againRes.isPass) throw "Migrating an up to date main state failed";
+  });
+
+  it("Migrate the user's loan and today's stats", async () => {
+    const userLoanRes = await connectivity.migrateUserLoan(provider.publicKey);
+    if (!userLoanRes.isPass) throw "Failed to migrate user loan";
+    const dailyStatsRes = await connectivity.migrateDailyStats(Math.floor(Date.now() / 1000));
+    if (!dailyStatsRes.isPass) throw "Failed to migrate daily stats";
+  });
+
+  it("Migrate the user's launch buyer account", async function () {
+    const launchBuyer = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("launch-buyer"), provider.publicKey.toBuffer()],
+      programId
+    )[0];
+    // only wallets that bought during the anti-bot window or the presale have one
+    if (!(await connection.getAccountInfo(launchBuyer))) this.skip();
+    const launchBuyerRes = await connectivity.migrateLaunchBuyer(provider.publicKey);
+    if (!launchBuyerRes.isPass) throw "Failed to migrate launch buyer";
+  });
+});
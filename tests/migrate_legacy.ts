@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { readFileSync } from "fs";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+// Needs a fresh validator holding the main and global state with the baseline layout:
+// solana-test-validator --reset \
+//   --bpf-program HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi target/deploy/mushi_program.so \
+//   --account 9M1CJmF5bpknM4Lp7gDYcQvyYXxzLas1VneAQwGzuaF4 tests/fixtures/legacy_main_state.json \
+//   --account yK3zjk5YRUu7MzK8v2doPoT3KxDePq4YibUQZTAi2hj tests/fixtures/legacy_global_state.json \
+//   --account 9x2c39LqRNockQpZvYCWt6nVSmeCHD1vRo9VPjUsQ5et tests/fixtures/legacy_vault.json
+// The main state fixture is admin legacy_admin.json, fee receiver 8CHN…Nvsg, buy fee 980, sell fee 975 and
+// buy fee leverage 10. The global state has 2 SOL borrowed and the vault holds 10 SOL above its rent.
+const log = console.log;
+describe("mushi_program_migrate_legacy", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const legacyAdmin = web3.Keypair.fromSecretKey(
+    Uint8Array.from(JSON.parse(readFileSync("tests/fixtures/legacy_admin.json", "utf8")))
+  );
+  const feeReceiver = new web3.PublicKey("8CHNnNzHme7hVv2Qw2WHbxX54EWJ6NMkjJ1zRTEkNvsg");
+  const mainState = web3.PublicKey.findProgramAddressSync([Buffer.from("main_state")], programId)[0];
+  // discriminator and the five legacy fields
+  const legacySize = 8 + 32 + 32 + 3 * 8;
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: new anchor.Wallet(legacyAdmin),
+    programId,
+  });
+
+  before(async function () {
+    const account = await connection.getAccountInfo(mainState);
+    if (!account || account.data.length !== legacySize) this.skip();
+    const airdrop = await connection.requestAirdrop(legacyAdmin.publicKey, web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+  });
+
+  it("Migrate a main state with the baseline layout", async () => {
+    const res = await connectivity.migrateMainState();
+    if (!res.isPass) throw "Failed to migrate the legacy main state";
+    await sleep(5_000);
+
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to read the migrated main state";
+    log({ info });
+    if (!info.admins[0].equals(legacyAdmin.publicKey) || info.adminThreshold !== 1) throw "Admin was not carried over";
+    if (info.admins.slice(1).some((admin) => !admin.equals(web3.PublicKey.default))) throw "Legacy bytes leaked into the admins";
+    if (!info.feeRecipients[0].address.equals(feeReceiver) || info.feeRecipients[0].weightBps !== 10_000)
+      throw "Fee receiver was not carried over";
+    if (info.feeRecipients.length !== 1) throw "Legacy bytes leaked into the fee recipients";
+    const schedule = info.feeSchedule;
+    // buy fee 980 keeps 2% of the tokens in the backing, sell fee 975 is 1.25% treasury and 1.25% backing
+    if (schedule.buyTreasuryBps !== 100 || schedule.buyReferralBps !== 25 || schedule.buyBackingBps !== 200)
+      throw "Buy fees were not mapped";
+    if (schedule.sellTreasuryBps !== 125 || schedule.sellReferralBps !== 0 || schedule.sellBackingBps !== 125)
+      throw "Sell fees were not mapped";
+    if (info.buyFeeLeverage !== 10) throw "Buy fee leverage was not carried over";
+    if (info.protocolFeeShare !== 300 || info.flashCloseFee !== 10) throw "Legacy loan fees were not set";
+    if (info.pauseFlags !== 0 || info.referralBindingDuration !== 0) throw "New fields are not zeroed";
+    if (info.feeChangeLimits.flashCloseFee === 0) throw "Fee change limits were not set";
+
+    const againRes = await connectivity.migrateMainState();
+    if (!againRes.isPass) throw "Migrating the migrated main state failed";
+  });
+
+  it("Migrate a global state with the baseline layout", async () => {
+    const res = await connectivity.migrateGlobalState();
+    if (!res.isPass) throw "Failed to migrate the legacy global state";
+    await sleep(5_000);
+
+    const info = await connectivity.getGlobalInfo();
+    if (!info) throw "Failed to read the migrated global state";
+    log({ info });
+    if (info.totalBorrowed !== 2 * web3.LAMPORTS_PER_SOL) throw "Total borrowed was not carried over";
+    // the vault balance above its rent, which made the backing before the field existed
+    if (info.backing !== 10 * web3.LAMPORTS_PER_SOL) throw `Backing seeded to ${info.backing}`;
+    if (info.accruedFees !== 0 || info.referralRewards !== 0) throw "New fields are not zeroed";
+
+    const againRes = await connectivity.migrateGlobalState();
+    if (!againRes.isPass) throw "Migrating the migrated global state failed";
+    const again = await connectivity.getGlobalInfo();
+    if (again?.backing !== info.backing) throw "Backing was seeded again";
+  });
+});
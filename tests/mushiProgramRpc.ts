@@ -12,15 +12,62 @@ const Seeds = {
   mainState: Buffer.from("main_state"),
   globalState: Buffer.from("global_stats"),
   vault: Buffer.from("vault"),
+  promotion: Buffer.from("promotion"),
+  referrer: Buffer.from("referrer"),
+  referralCode: Buffer.from("referral-code"),
+  pendingConfig: Buffer.from("pending-config"),
+  roles: Buffer.from("roles"),
+  teamVesting: Buffer.from("team-vesting"),
+  launchBuyer: Buffer.from("launch-buyer"),
 };
 const log = console.log;
 export type Result<T, E = string> =
   | { isPass: true; info: T }
   | { isPass: false; info: E };
 export type SendTxResult = Result<{ txSignature: string }, string>;
+export type Role =
+  | "configManager"
+  | "feeManager"
+  | "pauser"
+  | "treasuryClaimer"
+  | "launchOperator";
+
+export const PauseFlags = {
+  buy: 1 << 0,
+  sell: 1 << 1,
+  newLoans: 1 << 2,
+  loanModifications: 1 << 3,
+  liquidation: 1 << 4,
+};
+export type InitializeInput = {
+  feeRecipients: FeeRecipientInfo[];
+  feeSchedule: FeeScheduleInfo;
+  buyFeeLeverage: number;
+  protocolFeeShare: number;
+  flashCloseFee: number;
+};
+// configuration every test initializes with, tests override the fields they check
+export const DEFAULT_INITIALIZE_INPUT: InitializeInput = {
+  feeSchedule: {
+    buyTreasuryBps: 100,
+    buyReferralBps: 25,
+    buyBackingBps: 125,
+    sellTreasuryBps: 125,
+    sellReferralBps: 0,
+    sellBackingBps: 125,
+  },
+  buyFeeLeverage: 10,
+  protocolFeeShare: 300,
+  flashCloseFee: 10,
+  feeRecipients: [
+    { address: new web3.PublicKey("8CHNnNzHme7hVv2Qw2WHbxX54EWJ6NMkjJ1zRTEkNvsg"), weightBps: 10_000 },
+  ],
+};
 export const TOKEN_DECIMALS_HELPER = 1_000_000_000; // 9 decimals
 export const SOL_DECIMALS_HELPER = 1_000_000_000; // 9 decimals
 const SECONDS_IN_A_DAY = 86400;
+const MAX_FEE_TIERS = 4;
+const VOLUME_WINDOW_DAYS = 30;
 const associatedTokenProgram = ASSOCIATED_TOKEN_PROGRAM_ID;
 const mplProgram = new web3.PublicKey(
   "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
@@ -32,12 +79,83 @@ const tokenProgram = TOKEN_PROGRAM_ID;
 export async function sleep(ms: number) {
   return new Promise((resolve) => setTimeout(resolve, ms));
 }
+
+/**
+ * Returns a new wallet funded by an airdrop, for tests that need a user without loan or referrer
+ */
+export async function fundedKeypair(connection: web3.Connection, sol: number): Promise<web3.Keypair> {
+  const keypair = web3.Keypair.generate();
+  const signature = await connection.requestAirdrop(keypair.publicKey, sol * web3.LAMPORTS_PER_SOL);
+  await connection.confirmTransaction(signature, "confirmed");
+  return keypair;
+}
+
+/**
+ * Port of the program's `get_interest_fee`, in lamports, before any promotion
+ */
+export function getInterestFee(lamports: number, numberOfDays: number): number {
+  const totalInterest = new BN(39_000_000).muln(numberOfDays).divn(365).addn(1_000_000);
+  return new BN(lamports).mul(totalInterest).div(new BN(1_000_000_000)).toNumber();
+}
+export type FeeScheduleInfo = {
+  buyTreasuryBps: number;
+  buyReferralBps: number;
+  buyBackingBps: number;
+  sellTreasuryBps: number;
+  sellReferralBps: number;
+  sellBackingBps: number;
+};
+export type FeeChangeLimitsInfo = {
+  tradeFeeBps: number;
+  buyFeeLeverage: number;
+  protocolFeeShare: number;
+  flashCloseFee: number;
+};
+export type FeeRecipientInfo = {
+  address: web3.PublicKey;
+  weightBps: number;
+};
+/**
+ * Fields left undefined are not changed. Values are raw program units.
+ */
+export type ConfigChangeInput = {
+  feeRecipients?: FeeRecipientInfo[];
+  feeSchedule?: FeeScheduleInfo;
+  buyFeeLeverage?: number;
+  protocolFeeShare?: number;
+  flashCloseFee?: number;
+  // padded with unused tiers up to MAX_FEE_TIERS
+  feeTiers?: { minVolume: number; discountBps: number }[];
+  referralBindingDuration?: number;
+  leverageReferralShare?: number;
+  loanReferralShare?: number;
+  maxIntegratorFeeBps?: number;
+  feeChangeLimits?: FeeChangeLimitsInfo;
+  configTimelock?: number;
+  guardian?: web3.PublicKey;
+  // all zero to end the presale
+  presaleRoot?: number[];
+};
 export type MainStateInfo = {
-  admin: web3.PublicKey;
-  feeReceiver: web3.PublicKey;
-  sellFee: number;
-  buyFee: number;
+  admins: web3.PublicKey[];
+  adminThreshold: number;
+  pendingAdmins: web3.PublicKey[];
+  feeRecipients: FeeRecipientInfo[];
+  feeSchedule: FeeScheduleInfo;
   buyFeeLeverage: number;
+  protocolFeeShare: number;
+  flashCloseFee: number;
+  referralBindingDuration: number;
+  leverageReferralShare: number;
+  loanReferralShare: number;
+  maxIntegratorFeeBps: number;
+  // seconds a proposed config change waits before it can be executed
+  configTimelock: number;
+  guardian: web3.PublicKey;
+  pauseFlags: number;
+  feeChangeLimits: FeeChangeLimitsInfo;
+  // fees at the start of `feeBaseline.day` (timestamp / 86400), daily changes are measured from them
+  feeBaseline: { day: number; buyFeeLeverage: number; protocolFeeShare: number; flashCloseFee: number };
 };
 export type GlobalStateInfo = {
   started: boolean;
@@ -47,6 +165,34 @@ export type GlobalStateInfo = {
   totalBorrowed: number;
   totalCollateral: number;
   lastPrice: number;
+  accruedFees: number;
+  backing: number;
+  referralRewards: number;
+  relaunchEta: number;
+  tradingStartTs: number;
+  antiBotEndTs: number;
+  antiBotMaxBuy: number;
+};
+export type UserStatsInfo = {
+  totalVolume: number;
+  // day (timestamp / 86400) of the last recorded trade
+  lastTradeDay: number;
+  // ring buffer indexed by day % 30
+  dailyVolume: number[];
+  // volume of the last 30 days as the program counts it for the fee tiers
+  windowVolume: number;
+};
+export type IntegratorInfo = {
+  // null leaves the integrator account out of the transaction
+  address: web3.PublicKey | null;
+  feeBps: number;
+};
+export type ReferrerInfo = {
+  authority: web3.PublicKey;
+  referredVolume: number;
+  totalEarned: number;
+  pendingRewards: number;
+  refereeCount: number;
 };
 export type UserLoanInfo = {
   endDate: string;
@@ -125,7 +271,12 @@ export class MushiProgramRpc {
   private mainState: web3.PublicKey;
   private globalState: web3.PublicKey;
   private vaultOwner: web3.PublicKey;
+  private promotion: web3.PublicKey;
+  private pendingConfigChange: web3.PublicKey;
+  private roles: web3.PublicKey;
   private provider: AnchorProvider;
+  // name of the program error that failed the last sent tx, if it failed with one
+  lastErrorCode: string | null = null;
 
   constructor({
     rpc,
@@ -155,12 +306,31 @@ export class MushiProgramRpc {
       [Seeds.vault],
       this.programId
     )[0];
+    this.promotion = web3.PublicKey.findProgramAddressSync(
+      [Seeds.promotion],
+      this.programId
+    )[0];
+    this.pendingConfigChange = web3.PublicKey.findProgramAddressSync(
+      [Seeds.pendingConfig],
+      this.programId
+    )[0];
+    this.roles = web3.PublicKey.findProgramAddressSync(
+      [Seeds.roles],
+      this.programId
+    )[0];
+  }
+
+  // the promotion account is optional and only exists while one is scheduled
+  async getPromotion(): Promise<web3.PublicKey | null> {
+    const info = await this.connection.getAccountInfo(this.promotion);
+    return info ? this.promotion : null;
   }
 
   async sendTx(
     ixs: web3.TransactionInstruction[],
     signers?: web3.Keypair[]
   ): Promise<string | null> {
+    this.lastErrorCode = null;
     try {
       const payerKey = this.provider.publicKey;
       const recentBlockhash = (await this.connection.getLatestBlockhash())
@@ -194,6 +364,8 @@ export class MushiProgramRpc {
               })
               .catch(() => null)
               .then((res) => res?.value);
+            const errorLog = simRes?.logs?.find((line) => line.includes("Error Code: "));
+            this.lastErrorCode = errorLog?.match(/Error Code: (\w+)/)?.[1] ?? null;
             log({ txSignature });
             log({ simRes });
             log({ txSignatureRes: res.value });
@@ -219,184 +391,1249 @@ export class MushiProgramRpc {
     }
   }
 
-  async getMainStateInfo(): Promise<MainStateInfo | null> {
+  async getMainStateInfo(): Promise<MainStateInfo | null> {
+    try {
+      const {
+        admins,
+        adminThreshold,
+        pendingAdmins,
+        feeRecipients,
+        feeSchedule,
+        buyFeeLeverage,
+        protocolFeeShare,
+        flashCloseFee,
+        referralBindingDuration,
+        leverageReferralShare,
+        loanReferralShare,
+        maxIntegratorFeeBps,
+        configTimelock,
+        guardian,
+        pauseFlags,
+        feeChangeLimits,
+        feeBaseline,
+      } =
+        await this.program.account.mainState.fetch(this.mainState);
+      return {
+        admins: admins.filter((admin) => !admin.equals(web3.PublicKey.default)),
+        adminThreshold: Number(adminThreshold.toString()),
+        pendingAdmins: pendingAdmins.filter((admin) => !admin.equals(web3.PublicKey.default)),
+        feeSchedule: {
+          buyTreasuryBps: Number(feeSchedule.buyTreasuryBps.toString()),
+          buyReferralBps: Number(feeSchedule.buyReferralBps.toString()),
+          buyBackingBps: Number(feeSchedule.buyBackingBps.toString()),
+          sellTreasuryBps: Number(feeSchedule.sellTreasuryBps.toString()),
+          sellReferralBps: Number(feeSchedule.sellReferralBps.toString()),
+          sellBackingBps: Number(feeSchedule.sellBackingBps.toString()),
+        },
+        buyFeeLeverage: Number(buyFeeLeverage.toString()) / ONE_BASIS_POINTS,
+        protocolFeeShare: Number(protocolFeeShare.toString()) / ONE_BASIS_POINTS,
+        flashCloseFee: Number(flashCloseFee.toString()) / ONE_BASIS_POINTS,
+        referralBindingDuration: Number(referralBindingDuration.toString()),
+        leverageReferralShare: Number(leverageReferralShare.toString()) / ONE_BASIS_POINTS,
+        loanReferralShare: Number(loanReferralShare.toString()) / ONE_BASIS_POINTS,
+        maxIntegratorFeeBps: Number(maxIntegratorFeeBps.toString()),
+        configTimelock: Number(configTimelock.toString()),
+        guardian,
+        pauseFlags,
+        feeChangeLimits: {
+          tradeFeeBps: Number(feeChangeLimits.tradeFeeBps.toString()),
+          buyFeeLeverage: Number(feeChangeLimits.buyFeeLeverage.toString()),
+          protocolFeeShare: Number(feeChangeLimits.protocolFeeShare.toString()),
+          flashCloseFee: Number(feeChangeLimits.flashCloseFee.toString()),
+        },
+        feeBaseline: {
+          day: Number(feeBaseline.day.toString()),
+          buyFeeLeverage: Number(feeBaseline.buyFeeLeverage.toString()),
+          protocolFeeShare: Number(feeBaseline.protocolFeeShare.toString()),
+          flashCloseFee: Number(feeBaseline.flashCloseFee.toString()),
+        },
+        feeRecipients: feeRecipients
+          .filter((recipient) => !recipient.weightBps.isZero())
+          .map((recipient) => ({
+            address: recipient.address,
+            weightBps: Number(recipient.weightBps.toString()),
+          })),
+      };
+    } catch (getMainStateInfoError) {
+      log({ getMainStateInfoError });
+      return null;
+    }
+  }
+
+  async getGlobalInfo(): Promise<GlobalStateInfo | null> {
+    try {
+      const { tokenSupply, token, started, lastLiquidationDate, totalBorrowed, totalCollateral, lastPrice, accruedFees, backing, referralRewards, relaunchEta, tradingStartTs, antiBotEndTs, antiBotMaxBuy } =
+        await this.program.account.globalStats.fetch(this.globalState);
+      return {
+        tokenSupply: Number(tokenSupply.toString()),
+        token,
+        started,
+        lastLiquidationDate: Number(lastLiquidationDate.toString()),
+        totalBorrowed: Number(totalBorrowed.toString()),
+        totalCollateral: Number(totalCollateral.toString()),
+        lastPrice: Number(lastPrice.toString()),
+        accruedFees: Number(accruedFees.toString()),
+        backing: Number(backing.toString()),
+        referralRewards: Number(referralRewards.toString()),
+        relaunchEta: Number(relaunchEta.toString()),
+        tradingStartTs: Number(tradingStartTs.toString()),
+        antiBotEndTs: Number(antiBotEndTs.toString()),
+        antiBotMaxBuy: Number(antiBotMaxBuy.toString()) / SOL_DECIMALS_HELPER,
+      };
+    } catch (getGlobalStateInfoError) {
+      log({ getGlobalStateInfoError });
+      return null;
+    }
+  }
+
+  getReferrer(authority: web3.PublicKey): web3.PublicKey {
+    return web3.PublicKey.findProgramAddressSync(
+      [Seeds.referrer, authority.toBuffer()],
+      this.programId
+    )[0];
+  }
+
+  /**
+   * Returns the referrer PDA the user is bound to, or null when the user has no active binding
+   */
+  async getBoundReferrer(user: web3.PublicKey): Promise<web3.PublicKey | null> {
+    try {
+      const userStats = await this.program.account.userStats.fetch(
+        web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("user-stats"), user.toBuffer()],
+          this.programId
+        )[0]
+      );
+      if (userStats.referrer.equals(web3.PublicKey.default)) return null;
+      const { referralBindingDuration } = await this.program.account.mainState.fetch(this.mainState);
+      const duration = Number(referralBindingDuration.toString());
+      const boundAt = Number(userStats.referralBoundAt.toString());
+      if (duration != 0 && Date.now() / 1000 >= boundAt + duration) return null;
+      return this.getReferrer(userStats.referrer);
+    } catch {
+      // the user stats account is created on the first trade
+      return null;
+    }
+  }
+
+  /**
+   * Returns null until the user's first trade
+   */
+  async getUserStatsInfo(user: web3.PublicKey = this.provider.publicKey): Promise<UserStatsInfo | null> {
+    try {
+      const { totalVolume, lastTradeDay, dailyVolume } = await this.program.account.userStats.fetch(
+        web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("user-stats"), user.toBuffer()],
+          this.programId
+        )[0]
+      );
+      const today = Math.floor(Date.now() / 1000 / SECONDS_IN_A_DAY);
+      const lastDay = Number(lastTradeDay.toString());
+      const volumes = dailyVolume.map((volume) => Number(volume.toString()));
+      let windowVolume = 0;
+      for (let day = Math.max(today, lastDay) - VOLUME_WINDOW_DAYS + 1; day <= Math.min(lastDay, today); day++) {
+        windowVolume += volumes[day % VOLUME_WINDOW_DAYS];
+      }
+      return {
+        totalVolume: Number(totalVolume.toString()),
+        lastTradeDay: lastDay,
+        dailyVolume: volumes,
+        windowVolume,
+      };
+    } catch {
+      return null;
+    }
+  }
+
+  /**
+   * Derives the PDA of a referral code, normalized the way the program does
+   */
+  getReferralCode(code: string): web3.PublicKey {
+    return web3.PublicKey.findProgramAddressSync(
+      [Seeds.referralCode, Buffer.from(code.toLowerCase())],
+      this.programId
+    )[0];
+  }
+
+  async getReferrerInfo(authority: web3.PublicKey): Promise<ReferrerInfo | null> {
+    try {
+      const referrerData = await this.program.account.referrer.fetch(
+        this.getReferrer(authority)
+      );
+      return {
+        authority: referrerData.authority,
+        referredVolume: Number(referrerData.referredVolume.toString()),
+        totalEarned: Number(referrerData.totalEarned.toString()),
+        pendingRewards: Number(referrerData.pendingRewards.toString()),
+        refereeCount: Number(referrerData.refereeCount.toString()),
+      };
+    } catch (getReferrerInfoError) {
+      log({ getReferrerInfoError });
+      return null;
+    }
+  }
+
+  async getUserLoanInfo(user: web3.PublicKey): Promise<UserLoanInfo | null> {
+    try {
+      const userLoanAddress = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user-loan"), user.toBuffer()],
+        this.programId
+      )[0];
+
+      const userLoanData = await this.program.account.userLoan.fetch(userLoanAddress);
+      log({ userLoanData: userLoanData, userEndDate: getDateStringFromTimestamp(Number(userLoanData.endDate.toString())) });
+      return {
+        endDate: userLoanData.endDate.toString(),
+        borrowed: Number(userLoanData.borrowed.toString()),
+        collateral: Number(userLoanData.collateral.toString()),
+      };
+    } catch (getUserLoanInfoError) {
+      log({ getUserLoanInfoError });
+      return null;
+    }
+  }
+
+  /**
+   * Fields missing from `overrides` come from `DEFAULT_INITIALIZE_INPUT`
+   */
+  async initialize(overrides: Partial<InitializeInput> = {}): Promise<SendTxResult> {
+    try {
+      const input = { ...DEFAULT_INITIALIZE_INPUT, ...overrides };
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .initMainState({
+          feeRecipients: input.feeRecipients.map((recipient) => ({
+            address: recipient.address,
+            weightBps: new BN(recipient.weightBps),
+          })),
+          feeSchedule: {
+            buyTreasuryBps: new BN(input.feeSchedule.buyTreasuryBps),
+            buyReferralBps: new BN(input.feeSchedule.buyReferralBps),
+            buyBackingBps: new BN(input.feeSchedule.buyBackingBps),
+            sellTreasuryBps: new BN(input.feeSchedule.sellTreasuryBps),
+            sellReferralBps: new BN(input.feeSchedule.sellReferralBps),
+            sellBackingBps: new BN(input.feeSchedule.sellBackingBps),
+          },
+          buyFeeLeverage: new BN(Math.trunc(input.buyFeeLeverage * ONE_BASIS_POINTS)),
+          protocolFeeShare: new BN(Math.trunc(input.protocolFeeShare * ONE_BASIS_POINTS)),
+          flashCloseFee: new BN(Math.trunc(input.flashCloseFee * ONE_BASIS_POINTS)),
+        })
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          globalState: this.globalState,
+          roles: this.roles,
+          systemProgram,
+        })
+        .instruction();
+      const ixs = [
+        web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
+        ix,
+      ];
+      const txSignature = await this.sendTx(ixs, []);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (initializeError) {
+      log({ initializeError });
+      return { isPass: false, info: "failed to process the input" };
+    }
+  }
+
+  async start(input: {
+    tokenName: string;
+    tokenSymbol: string;
+    tokenUri: string;
+    solAmount: number;
+    // token amounts are raw base units
+    tokensPerSol?: number;
+    burnAmount?: number;
+    teamAllocation?: number;
+    teamWallet?: web3.PublicKey;
+    // seconds from the launch
+    teamCliffDuration?: number;
+    teamVestingDuration?: number;
+    // unix timestamp, 0 to open trading right away
+    tradingStartTs?: number;
+    antiBotDuration?: number;
+    // SOL per wallet during the anti-bot window
+    antiBotMaxBuy?: number;
+    sellerFeeBasisPoints?: number;
+    isMutable?: boolean;
+  }, coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const { tokenName, tokenSymbol, tokenUri, solAmount } = input;
+      const teamWallet = input.teamWallet ?? null;
+      const tokenKp = web3.Keypair.generate();
+      const token = tokenKp.publicKey;
+      const teamVesting = teamWallet && this.getTeamVesting(token);
+      const authority = this.provider.publicKey;
+      const tokenVault = getAssociatedTokenAddressSync(
+        token,
+        this.vaultOwner,
+        true
+      );
+      const tokenMetadataAccount = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), mplProgram.toBuffer(), token.toBuffer()],
+        mplProgram
+      )[0];
+
+      const ix = await this.program.methods
+        .start({
+          solAmount: new BN(
+            Math.trunc(solAmount * SOL_DECIMALS_HELPER)
+          ),
+          tokenName,
+          tokenSymbol,
+          tokenUri,
+          tokensPerSol: new BN(input.tokensPerSol ?? 1_000_000_000_000),
+          burnAmount: new BN(input.burnAmount ?? 1_000_000_000),
+          teamAllocation: new BN(input.teamAllocation ?? 0),
+          teamCliffDuration: new BN(input.teamCliffDuration ?? 0),
+          teamVestingDuration: new BN(input.teamVestingDuration ?? 0),
+          sellerFeeBasisPoints: input.sellerFeeBasisPoints ?? 100,
+          isMutable: input.isMutable ?? false,
+          tradingStartTs: new BN(input.tradingStartTs ?? 0),
+          antiBotDuration: new BN(input.antiBotDuration ?? 0),
+          antiBotMaxBuy: new BN(Math.trunc((input.antiBotMaxBuy ?? 0) * SOL_DECIMALS_HELPER)),
+        })
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          globalState: this.globalState,
+          roles: this.roles,
+          tokenVault: tokenVault,
+          tokenVaultOwner: this.vaultOwner,
+          associatedTokenProgram,
+          mplProgram,
+          systemProgram,
+          sysvarRent,
+          tokenProgram,
+          token,
+          tokenMetadataAccount,
+          teamWallet,
+          teamVesting,
+          teamVestingVault: teamVesting && getAssociatedTokenAddressSync(token, teamVesting, true),
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const ixs = [
+        web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
+        ix,
+      ];
+      const txSignature = await this.sendTx(ixs, [tokenKp, ...coSigners]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (initializeError) {
+      log({ initializeError });
+      return { isPass: false, info: "failed to process the input" };
+    }
+  }
+
+  /**
+   * Admins co-signing an admin instruction besides the wallet, to reach the admin threshold
+   */
+  coSignerAccounts(coSigners: web3.Keypair[]): web3.AccountMeta[] {
+    return coSigners.map((coSigner) => ({
+      pubkey: coSigner.publicKey,
+      isSigner: true,
+      isWritable: false,
+    }));
+  }
+
+  /**
+   * Callable by the guardian, the pauser or any admin
+   */
+  async pause(flags: number): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .pause(flags)
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (pauseError) {
+      log({ pauseError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async unpause(flags: number, coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .unpause(flags)
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (unpauseError) {
+      log({ unpauseError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async scheduleRelaunch(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .scheduleRelaunch()
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          globalState: this.globalState,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (scheduleRelaunchError) {
+      log({ scheduleRelaunchError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async cancelRelaunch(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .cancelRelaunch()
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          globalState: this.globalState,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (cancelRelaunchError) {
+      log({ cancelRelaunchError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Refused while loans are outstanding or tokens are held outside the vault and the team vesting,
+   * the team vesting is passed when the launch has one unless `withTeamVesting` is false
+   */
+  async executeRelaunch(coSigners: web3.Keypair[] = [], withTeamVesting = true): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const globalInfo = await this.getGlobalInfo();
+      if (!globalInfo) throw "failed to get global state info";
+      const token = globalInfo.token;
+      const teamVestingVault = getAssociatedTokenAddressSync(token, this.getTeamVesting(token), true);
+      const hasTeamVesting = withTeamVesting && (await this.connection.getAccountInfo(teamVestingVault)) !== null;
+      const ix = await this.program.methods
+        .executeRelaunch()
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          globalState: this.globalState,
+          token,
+          tokenVaultOwner: this.vaultOwner,
+          tokenVault: getAssociatedTokenAddressSync(token, this.vaultOwner, true),
+          teamVestingVault: hasTeamVesting ? teamVestingVault : null,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (executeRelaunchError) {
+      log({ executeRelaunchError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async migrateMainState(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .migrateMainState()
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          roles: this.roles,
+          systemProgram,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (migrateMainStateError) {
+      log({ migrateMainStateError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async migrateGlobalState(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .migrateGlobalState()
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          globalState: this.globalState,
+          tokenVaultOwner: this.vaultOwner,
+          systemProgram,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (migrateGlobalStateError) {
+      log({ migrateGlobalStateError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Permissionless, the wallet pays the extra rent
+   */
+  async migrateUserLoan(user: web3.PublicKey): Promise<SendTxResult> {
+    try {
+      const payer = this.provider.publicKey;
+      const ix = await this.program.methods
+        .migrateUserLoan()
+        .accounts({
+          payer,
+          user,
+          userLoan: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("user-loan"), user.toBuffer()],
+            this.programId
+          )[0],
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (migrateUserLoanError) {
+      log({ migrateUserLoanError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Permissionless, the wallet pays the extra rent
+   */
+  async migrateLaunchBuyer(user: web3.PublicKey): Promise<SendTxResult> {
+    try {
+      const payer = this.provider.publicKey;
+      const ix = await this.program.methods
+        .migrateLaunchBuyer()
+        .accounts({
+          payer,
+          user,
+          launchBuyer: web3.PublicKey.findProgramAddressSync(
+            [Seeds.launchBuyer, user.toBuffer()],
+            this.programId
+          )[0],
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (migrateLaunchBuyerError) {
+      log({ migrateLaunchBuyerError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Permissionless, migrates the daily stats of the day containing `timestamp` (seconds)
+   */
+  async migrateDailyStats(timestamp: number): Promise<SendTxResult> {
+    try {
+      const payer = this.provider.publicKey;
+      const ix = await this.program.methods
+        .migrateDailyStats(new BN(timestamp))
+        .accounts({
+          payer,
+          dailyState: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("daily-stats"), Buffer.from(getDateStringFromTimestamp(timestamp))],
+            this.programId
+          )[0],
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (migrateDailyStatsError) {
+      log({ migrateDailyStatsError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Returns the user's launch buyer PDA during the anti-bot window, null afterwards
+   */
+  async getLaunchBuyer(user: web3.PublicKey): Promise<web3.PublicKey | null> {
+    const globalInfo = await this.getGlobalInfo();
+    if (!globalInfo || Date.now() / 1000 >= globalInfo.antiBotEndTs) return null;
+    return web3.PublicKey.findProgramAddressSync(
+      [Seeds.launchBuyer, user.toBuffer()],
+      this.programId
+    )[0];
+  }
+
+  // one vesting account per launch, seeded by its mint
+  getTeamVesting(token: web3.PublicKey): web3.PublicKey {
+    return web3.PublicKey.findProgramAddressSync(
+      [Seeds.teamVesting, token.toBuffer()],
+      this.programId
+    )[0];
+  }
+
+  /**
+   * `token` defaults to the mint of the current launch
+   */
+  async getTeamVestingInfo(token?: web3.PublicKey): Promise<{
+    beneficiary: web3.PublicKey;
+    total: number;
+    claimed: number;
+    cliffTs: number;
+    endTs: number;
+  } | null> {
+    try {
+      const mint = token ?? (await this.getGlobalInfo())?.token;
+      if (!mint) throw "Failed to get global state info";
+      const { beneficiary, total, claimed, cliffTs, endTs } =
+        await this.program.account.teamVesting.fetch(this.getTeamVesting(mint));
+      return {
+        beneficiary,
+        total: Number(total.toString()),
+        claimed: Number(claimed.toString()),
+        cliffTs: Number(cliffTs.toString()),
+        endTs: Number(endTs.toString()),
+      };
+    } catch (getTeamVestingInfoError) {
+      log({ getTeamVestingInfoError });
+      return null;
+    }
+  }
+
+  /**
+   * `token` defaults to the mint of the current launch, earlier launches can still be claimed
+   */
+  async claimVested(token?: web3.PublicKey): Promise<SendTxResult> {
+    try {
+      const beneficiary = this.provider.publicKey;
+      if (!token) {
+        const globalInfo = await this.getGlobalInfo();
+        if (!globalInfo) throw "Failed to get global state info";
+        token = globalInfo.token;
+      }
+      const teamVesting = this.getTeamVesting(token);
+      const ix = await this.program.methods
+        .claimVested()
+        .accounts({
+          beneficiary,
+          teamVesting,
+          token,
+          teamVestingVault: getAssociatedTokenAddressSync(token, teamVesting, true),
+          beneficiaryAta: getAssociatedTokenAddressSync(token, beneficiary),
+          associatedTokenProgram,
+          tokenProgram,
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (claimVestedError) {
+      log({ claimVestedError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async getRoles(): Promise<Record<Role, web3.PublicKey> | null> {
+    try {
+      return await this.program.account.roles.fetch(this.roles);
+    } catch (getRolesError) {
+      log({ getRolesError });
+      return null;
+    }
+  }
+
+  async grantRole(
+    role: Role,
+    account: web3.PublicKey,
+    coSigners: web3.Keypair[] = []
+  ): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .grantRole({ [role]: {} } as any, account)
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          roles: this.roles,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (grantRoleError) {
+      log({ grantRoleError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async revokeRole(role: Role, coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .revokeRole({ [role]: {} } as any)
+        .accounts({
+          admin,
+          mainState: this.mainState,
+          roles: this.roles,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (revokeRoleError) {
+      log({ revokeRoleError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async proposeAdmins(
+    admins: web3.PublicKey[],
+    threshold: number,
+    coSigners: web3.Keypair[] = []
+  ): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .proposeAdmins(admins, new BN(threshold))
+        .accounts({
+          admin,
+          mainState: this.mainState,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (proposeAdminsError) {
+      log({ proposeAdminsError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Every admin of the pending set has to sign
+   */
+  async acceptAdmins(newAdmins: web3.Keypair[]): Promise<SendTxResult> {
+    try {
+      const ix = await this.program.methods
+        .acceptAdmins()
+        .accounts({
+          pendingAdmin: newAdmins[0].publicKey,
+          mainState: this.mainState,
+        })
+        .remainingAccounts(this.coSignerAccounts(newAdmins.slice(1)))
+        .instruction();
+      const txSignature = await this.sendTx([ix], newAdmins);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (acceptAdminsError) {
+      log({ acceptAdminsError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async cancelAdminTransfer(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const admin = this.provider.publicKey;
+      const ix = await this.program.methods
+        .cancelAdminTransfer()
+        .accounts({
+          admin,
+          mainState: this.mainState,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (cancelAdminTransferError) {
+      log({ cancelAdminTransferError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  toConfigChangeArgs(input: ConfigChangeInput) {
+    const bn = (value?: number) => (value === undefined ? null : new BN(value));
+    return {
+      feeRecipients: input.feeRecipients
+        ? input.feeRecipients.map((recipient) => ({
+            address: recipient.address,
+            weightBps: new BN(recipient.weightBps),
+          }))
+        : null,
+      feeSchedule: input.feeSchedule
+        ? {
+            buyTreasuryBps: new BN(input.feeSchedule.buyTreasuryBps),
+            buyReferralBps: new BN(input.feeSchedule.buyReferralBps),
+            buyBackingBps: new BN(input.feeSchedule.buyBackingBps),
+            sellTreasuryBps: new BN(input.feeSchedule.sellTreasuryBps),
+            sellReferralBps: new BN(input.feeSchedule.sellReferralBps),
+            sellBackingBps: new BN(input.feeSchedule.sellBackingBps),
+          }
+        : null,
+      buyFeeLeverage: bn(input.buyFeeLeverage),
+      protocolFeeShare: bn(input.protocolFeeShare),
+      flashCloseFee: bn(input.flashCloseFee),
+      feeTiers: input.feeTiers
+        ? [...input.feeTiers, ...Array(MAX_FEE_TIERS - input.feeTiers.length).fill({ minVolume: 0, discountBps: 0 })].map(
+            (tier) => ({
+              minVolume: new BN(tier.minVolume),
+              discountBps: new BN(tier.discountBps),
+            })
+          )
+        : null,
+      referralBindingDuration: bn(input.referralBindingDuration),
+      leverageReferralShare: bn(input.leverageReferralShare),
+      loanReferralShare: bn(input.loanReferralShare),
+      maxIntegratorFeeBps: bn(input.maxIntegratorFeeBps),
+      feeChangeLimits: input.feeChangeLimits
+        ? {
+            tradeFeeBps: new BN(input.feeChangeLimits.tradeFeeBps),
+            buyFeeLeverage: new BN(input.feeChangeLimits.buyFeeLeverage),
+            protocolFeeShare: new BN(input.feeChangeLimits.protocolFeeShare),
+            flashCloseFee: new BN(input.feeChangeLimits.flashCloseFee),
+          }
+        : null,
+      configTimelock: bn(input.configTimelock),
+      guardian: input.guardian ?? null,
+      presaleRoot: input.presaleRoot ?? null,
+    };
+  }
+
+  async getPendingConfigChange(): Promise<{ proposer: web3.PublicKey; eta: number } | null> {
+    try {
+      const { proposer, eta } = await this.program.account.pendingConfigChange.fetch(
+        this.pendingConfigChange
+      );
+      return { proposer, eta: Number(eta.toString()) };
+    } catch {
+      return null;
+    }
+  }
+
+  async proposeConfigChange(
+    input: ConfigChangeInput,
+    coSigners: web3.Keypair[] = []
+  ): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .proposeConfigChange(this.toConfigChangeArgs(input))
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+          pendingConfigChange: this.pendingConfigChange,
+          systemProgram,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (proposeConfigChangeError) {
+      log({ proposeConfigChangeError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async executeConfigChange(): Promise<SendTxResult> {
+    try {
+      const pending = await this.getPendingConfigChange();
+      if (!pending) throw "No pending config change";
+      const ix = await this.program.methods
+        .executeConfigChange()
+        .accounts({
+          mainState: this.mainState,
+          pendingConfigChange: this.pendingConfigChange,
+          proposer: pending.proposer,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (executeConfigChangeError) {
+      log({ executeConfigChangeError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async cancelConfigChange(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const pending = await this.getPendingConfigChange();
+      if (!pending) throw "No pending config change";
+      const ix = await this.program.methods
+        .cancelConfigChange()
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+          pendingConfigChange: this.pendingConfigChange,
+          proposer: pending.proposer,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (cancelConfigChangeError) {
+      log({ cancelConfigChangeError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  /**
+   * Applies a configuration change directly, only possible while no config timelock is set
+   */
+  async updateMainState(
+    input: ConfigChangeInput,
+    coSigners: web3.Keypair[] = []
+  ): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .updateMainState(this.toConfigChangeArgs(input))
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (updateMainStateError) {
+      log({ updateMainStateError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async schedulePromotion(input: {
+    startTs: number;
+    endTs: number;
+    buyTreasuryBps: number;
+    sellTreasuryBps: number;
+    interestFeeBps: number;
+  }, coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .schedulePromotion({
+          startTs: new BN(input.startTs),
+          endTs: new BN(input.endTs),
+          buyTreasuryBps: new BN(input.buyTreasuryBps),
+          sellTreasuryBps: new BN(input.sellTreasuryBps),
+          interestFeeBps: new BN(input.interestFeeBps),
+        })
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+          promotion: this.promotion,
+          systemProgram,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (schedulePromotionError) {
+      log({ schedulePromotionError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async cancelPromotion(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
     try {
-      const { admin, feeReceiver, sellFee, buyFee, buyFeeLeverage } =
-        await this.program.account.mainState.fetch(this.mainState);
-      return {
-        admin,
-        sellFee: Number(sellFee.toString()) / ONE_BASIS_POINTS,
-        buyFee: Number(buyFee.toString()) / ONE_BASIS_POINTS,
-        buyFeeLeverage: Number(buyFeeLeverage.toString()) / ONE_BASIS_POINTS,
-        feeReceiver,
-      };
-    } catch (getMainStateInfoError) {
-      log({ getMainStateInfoError });
-      return null;
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .cancelPromotion()
+        .accounts({
+          authority,
+          mainState: this.mainState,
+          roles: this.roles,
+          promotion: this.promotion,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (cancelPromotionError) {
+      log({ cancelPromotionError });
+      return { isPass: false, info: "failed to process input" };
     }
   }
 
-  async getGlobalInfo(): Promise<GlobalStateInfo | null> {
+  async claimFees(): Promise<SendTxResult> {
     try {
-      const { tokenSupply, token, started, lastLiquidationDate, totalBorrowed, totalCollateral, lastPrice } =
-        await this.program.account.globalStats.fetch(this.globalState);
-      return {
-        tokenSupply: Number(tokenSupply.toString()),
-        token,
-        started,
-        lastLiquidationDate: Number(lastLiquidationDate.toString()),
-        totalBorrowed: Number(totalBorrowed.toString()),
-        totalCollateral: Number(totalCollateral.toString()),
-        lastPrice: Number(lastPrice.toString()),
-      };
-    } catch (getGlobalStateInfoError) {
-      log({ getGlobalStateInfoError });
-      return null;
+      const claimer = this.provider.publicKey;
+      const mainStateInfo = await this.getMainStateInfo();
+      if (!mainStateInfo) throw "Failed to get main state info";
+      const ix = await this.program.methods
+        .claimFees()
+        .accounts({
+          claimer,
+          mainState: this.mainState,
+          roles: this.roles,
+          globalState: this.globalState,
+          tokenVaultOwner: this.vaultOwner,
+          systemProgram,
+        })
+        .remainingAccounts(
+          mainStateInfo.feeRecipients.map((recipient) => ({
+            pubkey: recipient.address,
+            isSigner: false,
+            isWritable: true,
+          }))
+        )
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (claimFeesError) {
+      log({ claimFeesError });
+      return { isPass: false, info: "failed to process input" };
     }
   }
 
-  async getUserLoanInfo(user: web3.PublicKey): Promise<UserLoanInfo | null> {
+  async donateToBacking(solAmount: number): Promise<SendTxResult> {
     try {
-      const userLoanAddress = web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("user-loan"), user.toBuffer()],
-        this.programId
-      )[0];
-
-      const userLoanData = await this.program.account.userLoan.fetch(userLoanAddress);
-      log({ userLoanData: userLoanData, userEndDate: getDateStringFromTimestamp(Number(userLoanData.endDate.toString())) });
-      return {
-        endDate: userLoanData.endDate.toString(),
-        borrowed: Number(userLoanData.borrowed.toString()),
-        collateral: Number(userLoanData.collateral.toString()),
-      };
-    } catch (getUserLoanInfoError) {
-      log({ getUserLoanInfoError });
-      return null;
+      const donor = this.provider.publicKey;
+      const ix = await this.program.methods
+        .donateToBacking(new BN(solAmount))
+        .accounts({
+          donor,
+          globalState: this.globalState,
+          tokenVaultOwner: this.vaultOwner,
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (donateToBackingError) {
+      log({ donateToBackingError });
+      return { isPass: false, info: "failed to process input" };
     }
   }
 
-  async initialize(input: {
-    feeReceiver: web3.PublicKey;
-    sellFee: number;
-    buyFee: number;
-    buyFeeLeverage: number;
-  }): Promise<SendTxResult> {
+  async syncBacking(coSigners: web3.Keypair[] = []): Promise<SendTxResult> {
     try {
-      log(Math.trunc(input.sellFee * ONE_BASIS_POINTS));
       const admin = this.provider.publicKey;
       const ix = await this.program.methods
-        .initMainState({
-          feeReceiver: input.feeReceiver,
-          sellFee: new BN(Math.trunc(input.sellFee * ONE_BASIS_POINTS)),
-          buyFee: new BN(Math.trunc(input.buyFee * ONE_BASIS_POINTS)),
-          buyFeeLeverage: new BN(Math.trunc(input.buyFeeLeverage * ONE_BASIS_POINTS)),
-        })
+        .syncBacking()
         .accounts({
           admin,
           mainState: this.mainState,
           globalState: this.globalState,
+          tokenVaultOwner: this.vaultOwner,
+        })
+        .remainingAccounts(this.coSignerAccounts(coSigners))
+        .instruction();
+      const txSignature = await this.sendTx([ix], coSigners);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (syncBackingError) {
+      log({ syncBackingError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async registerReferrer(): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .registerReferrer()
+        .accounts({
+          authority,
+          referrer: this.getReferrer(authority),
           systemProgram,
         })
         .instruction();
-      const ixs = [
-        web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
-        ix,
-      ];
-      const txSignature = await this.sendTx(ixs, []);
+      const txSignature = await this.sendTx([ix]);
       if (!txSignature) throw "failed to send tx";
       return { isPass: true, info: { txSignature } };
-    } catch (initializeError) {
-      log({ initializeError });
-      return { isPass: false, info: "failed to process the input" };
+    } catch (registerReferrerError) {
+      log({ registerReferrerError });
+      return { isPass: false, info: "failed to process input" };
     }
   }
 
-  async start(input: {
-    tokenName: string;
-    tokenSymbol: string;
-    tokenUri: string;
-    solAmount: number;
-  }): Promise<SendTxResult> {
+  async registerReferralCode(code: string): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .registerReferralCode(code)
+        .accounts({
+          authority,
+          referrer: this.getReferrer(authority),
+          referralCode: this.getReferralCode(code),
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (registerReferralCodeError) {
+      log({ registerReferralCodeError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async claimReferralRewards(): Promise<SendTxResult> {
+    try {
+      const authority = this.provider.publicKey;
+      const ix = await this.program.methods
+        .claimReferralRewards()
+        .accounts({
+          authority,
+          referrer: this.getReferrer(authority),
+          globalState: this.globalState,
+          tokenVaultOwner: this.vaultOwner,
+          systemProgram,
+        })
+        .instruction();
+      const txSignature = await this.sendTx([ix]);
+      if (!txSignature) throw "failed to send tx";
+      return { isPass: true, info: { txSignature } };
+    } catch (claimReferralRewardsError) {
+      log({ claimReferralRewardsError });
+      return { isPass: false, info: "failed to process input" };
+    }
+  }
+
+  async buy(
+    solAmount: number,
+    debug: boolean = false,
+    integrator: IntegratorInfo | null = null
+  ): Promise<SendTxResult> {
     try {
-      const { tokenName, tokenSymbol, tokenUri, solAmount } = input;
-      const tokenKp = web3.Keypair.generate();
-      const token = tokenKp.publicKey;
       const admin = this.provider.publicKey;
+      const globalInfo = await this.getGlobalInfo();
+      if (!globalInfo) throw "Failed to get global state info";
+      const { token } = globalInfo;
+      const mainStateInfo = await this.getMainStateInfo();
+      if (!mainStateInfo) throw "Failed to get main state info";
+
+      // Get the global state directly to access last_liquidation_date
+      const globalState = await this.program.account.globalStats.fetch(this.globalState);
+      const lastLiquidationDate = globalState.lastLiquidationDate;
+
+      const rawSolAmount = Math.trunc(solAmount * SOL_DECIMALS_HELPER);
+      const user = this.provider.publicKey;
+      const userAta = getAssociatedTokenAddressSync(token, user);
       const tokenVault = getAssociatedTokenAddressSync(
         token,
         this.vaultOwner,
         true
       );
-      const tokenMetadataAccount = web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("metadata"), mplProgram.toBuffer(), token.toBuffer()],
-        mplProgram
-      )[0];
-
+      
+      // Calculate the midnight timestamp in seconds (Unix timestamp) as the program does
+      const now = Math.floor(Date.now() / 1000); // Current time in seconds
+      const midnightTimestamp = now - (now % SECONDS_IN_A_DAY);
+      
+      // Get the date strings correctly formatted
+      // const currentDateString = getDateStringFromTimestamp(midnightTimestamp);
+      const currentDateString = getCurrentDateString();
+      const liquidationDateString = getDateStringFromTimestamp(Number(lastLiquidationDate));
+      
       const ix = await this.program.methods
-        .start({
-          solAmount: new BN(
-            Math.trunc(solAmount * SOL_DECIMALS_HELPER)
-          ),
-          tokenName,
-          tokenSymbol,
-          tokenUri,
-        })
+        .buy(new BN(rawSolAmount), new BN(integrator?.feeBps ?? 0))
         .accounts({
-          admin,
+          user,
           mainState: this.mainState,
           globalState: this.globalState,
-          tokenVault: tokenVault,
+          dailyState: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("daily-stats"), Buffer.from(currentDateString)],
+            this.programId
+          )[0],
+          userLoan: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("user-loan"), user.toBuffer()],
+            this.programId
+          )[0],
+          userStats: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("user-stats"), user.toBuffer()],
+            this.programId
+          )[0],
+          promotion: await this.getPromotion(),
+          referrer: await this.getBoundReferrer(user),
+          integrator: integrator?.address ?? null,
+          launchBuyer: await this.getLaunchBuyer(user),
+          lastLiquidationDateState: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("daily-stats"), Buffer.from(liquidationDateString)],
+            this.programId
+          )[0],
+          token,
+          userAta,
           tokenVaultOwner: this.vaultOwner,
+          tokenVault,
           associatedTokenProgram,
-          mplProgram,
-          systemProgram,
-          sysvarRent,
           tokenProgram,
-          token,
-          tokenMetadataAccount,
+          systemProgram,
         })
         .instruction();
+      
       const ixs = [
-        web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
+        web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 150_000 }),
         ix,
       ];
-      const txSignature = await this.sendTx(ixs, [tokenKp]);
+      
+      const txSignature = await this.sendTx(ixs);
       if (!txSignature) throw "failed to send tx";
       return { isPass: true, info: { txSignature } };
-    } catch (initializeError) {
-      log({ initializeError });
-      return { isPass: false, info: "failed to process the input" };
+    } catch (buyError) {
+      log({ buyError });
+      return { isPass: false, info: "failed to process input" };
     }
   }
 
-  // async updateMainState(input: {
-  //   feeReceiver?: web3.PublicKey;
-  //   admin?: web3.PublicKey;
-  // }): Promise<SendTxResult> {
-  //   try {
-  //     const admin = this.provider.publicKey;
-  //     const feeReceiver = input.feeReceiver ?? null;
-  //     const newAdmin = input.admin ?? null;
-  //     const ix = await this.program.methods
-  //       .updateMainState({ feeReceiver, admin: newAdmin })
-  //       .accounts({ admin, mainState: this.mainState })
-  //       .instruction();
-  //     const ixs = [
-  //       web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 100_000 }),
-  //       ix,
-  //     ];
-  //     const updateTxRes = await this.sendTx(ixs);
-  //     if (!updateTxRes) return { isPass: false, info: "failed to send tx" };
-  //     return { isPass: true, info: { txSignature: updateTxRes } };
-  //   } catch (updateMainStateError) {
-  //     log({ updateMainStateError });
-  //     return { isPass: false, info: "failed to process input" };
-  //   }
-  // }
-
-  async buy(
+  /**
+   * `maxBuy` (SOL) and `proof` come from the wallet's allowlist entry,
+   * whose leaf is keccak256(wallet || maxBuy in lamports as u64 little endian)
+   */
+  async presaleBuy(
     solAmount: number,
-    debug: boolean = false
+    maxBuy: number,
+    proof: number[][]
   ): Promise<SendTxResult> {
     try {
       const admin = this.provider.publicKey;
@@ -405,7 +1642,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       // Get the global state directly to access last_liquidation_date
       const globalState = await this.program.account.globalStats.fetch(this.globalState);
@@ -430,7 +1666,11 @@ export class MushiProgramRpc {
       const liquidationDateString = getDateStringFromTimestamp(Number(lastLiquidationDate));
       
       const ix = await this.program.methods
-        .buy(new BN(rawSolAmount))
+        .presaleBuy({
+          solAmount: new BN(rawSolAmount),
+          maxBuy: new BN(Math.trunc(maxBuy * SOL_DECIMALS_HELPER)),
+          proof,
+        })
         .accounts({
           user,
           mainState: this.mainState,
@@ -443,11 +1683,21 @@ export class MushiProgramRpc {
             [Buffer.from("user-loan"), user.toBuffer()],
             this.programId
           )[0],
+          userStats: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("user-stats"), user.toBuffer()],
+            this.programId
+          )[0],
+          promotion: await this.getPromotion(),
+          referrer: await this.getBoundReferrer(user),
+          integrator: null,
+          launchBuyer: web3.PublicKey.findProgramAddressSync(
+            [Seeds.launchBuyer, user.toBuffer()],
+            this.programId
+          )[0],
           lastLiquidationDateState: web3.PublicKey.findProgramAddressSync(
             [Buffer.from("daily-stats"), Buffer.from(liquidationDateString)],
             this.programId
           )[0],
-          feeReceiver,
           token,
           userAta,
           tokenVaultOwner: this.vaultOwner,
@@ -466,24 +1716,31 @@ export class MushiProgramRpc {
       const txSignature = await this.sendTx(ixs);
       if (!txSignature) throw "failed to send tx";
       return { isPass: true, info: { txSignature } };
-    } catch (buyError) {
-      log({ buyError });
+    } catch (presaleBuyError) {
+      log({ presaleBuyError });
       return { isPass: false, info: "failed to process input" };
     }
   }
 
+  /**
+   * Buys through a referrer, designated either by its pubkey or by one of its referral codes
+   */
   async buy_with_referral(
     solAmount: number,
-    referralPubkey: web3.PublicKey
+    referral: web3.PublicKey | string,
+    integrator: IntegratorInfo | null = null
   ): Promise<SendTxResult> {
     try {
+      const referralCode = typeof referral == "string" ? this.getReferralCode(referral) : null;
+      const referralPubkey = referralCode
+        ? (await this.program.account.referralCode.fetch(referralCode)).referrer
+        : (referral as web3.PublicKey);
       const admin = this.provider.publicKey;
       const globalInfo = await this.getGlobalInfo();
       if (!globalInfo) throw "Failed to get global state info";
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       // Get the global state directly to access last_liquidation_date
       const globalState = await this.program.account.globalStats.fetch(this.globalState);
@@ -509,7 +1766,8 @@ export class MushiProgramRpc {
       const ix = await this.program.methods
         .buyWithReferral({
           solAmount: new BN(rawSolAmount),
-          referralPubkey: referralPubkey
+          referralPubkey: referralCode ? null : referralPubkey,
+          integratorFeeBps: new BN(integrator?.feeBps ?? 0),
         })
         .accounts({
           common: {
@@ -524,11 +1782,18 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: this.getReferrer(referralPubkey),
+            integrator: integrator?.address ?? null,
+            launchBuyer: await this.getLaunchBuyer(user),
             lastLiquidationDateState: web3.PublicKey.findProgramAddressSync(
               [Buffer.from("daily-stats"), Buffer.from(liquidationDateString)],
               this.programId
             )[0],
-            feeReceiver,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -537,7 +1802,7 @@ export class MushiProgramRpc {
             tokenProgram,
             systemProgram,
           },
-          referral: referralPubkey,
+          referralCode,
         })
         .instruction();
       
@@ -557,7 +1822,8 @@ export class MushiProgramRpc {
 
   async sell(
     tokenAmount: number,
-    debug: boolean = false
+    debug: boolean = false,
+    integrator: IntegratorInfo | null = null
   ): Promise<SendTxResult> {
     try {
       const admin = this.provider.publicKey;
@@ -566,7 +1832,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       // Get the global state directly to access last_liquidation_date
       const globalState = await this.program.account.globalStats.fetch(this.globalState);
@@ -600,7 +1865,7 @@ export class MushiProgramRpc {
       }
       
       const ix = await this.program.methods
-        .sell(new BN(rawTokenAmount))
+        .sell(new BN(rawTokenAmount), new BN(integrator?.feeBps ?? 0))
         .accounts({
           user,
           mainState: this.mainState,
@@ -617,7 +1882,14 @@ export class MushiProgramRpc {
             [Buffer.from("user-loan"), user.toBuffer()],
             this.programId
           )[0],
-          feeReceiver,
+          userStats: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("user-stats"), user.toBuffer()],
+            this.programId
+          )[0],
+          promotion: await this.getPromotion(),
+          referrer: await this.getBoundReferrer(user),
+          integrator: integrator?.address ?? null,
+          launchBuyer: null,
           token,
           userAta,
           tokenVaultOwner: this.vaultOwner,
@@ -653,7 +1925,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       // Get the global state directly to access last_liquidation_date
       const globalState = await this.program.account.globalStats.fetch(this.globalState);
@@ -710,7 +1981,19 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            
+            userStats: web3.PublicKey.findProgramAddressSync(
+            
+              [Buffer.from("user-stats"), user.toBuffer()],
+            
+              this.programId
+            
+            )[0],
+            
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -745,7 +2028,8 @@ export class MushiProgramRpc {
   async leverage(
     solAmount: number,
     numberOfDays: number,
-    debug: boolean = false
+    debug: boolean = false,
+    integrator: IntegratorInfo | null = null
   ): Promise<SendTxResult> {
     try {
       const globalInfo = await this.getGlobalInfo();
@@ -753,7 +2037,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       // Get the global state directly to access last_liquidation_date
       const globalState = await this.program.account.globalStats.fetch(this.globalState);
@@ -790,7 +2073,7 @@ export class MushiProgramRpc {
       }
       
       const ix = await this.program.methods
-        .leverage(new BN(numberOfDays), new BN(rawSolAmount) )
+        .leverage(new BN(numberOfDays), new BN(rawSolAmount), new BN(integrator?.feeBps ?? 0))
         .accounts({
           common: {
             user,
@@ -808,7 +2091,14 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: integrator?.address ?? null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -845,7 +2135,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       const userLoanInfo = await this.getUserLoanInfo(this.provider.publicKey);
       if (!userLoanInfo) throw "Failed to get user loan info";
@@ -901,7 +2190,14 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -940,7 +2236,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       const userLoanInfo = await this.getUserLoanInfo(this.provider.publicKey);
       if (!userLoanInfo) throw "Failed to get user loan info";
@@ -996,7 +2291,14 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -1036,7 +2338,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       const userLoanInfo = await this.getUserLoanInfo(this.provider.publicKey);
       if (!userLoanInfo) throw "Failed to get user loan info";
@@ -1092,7 +2393,14 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -1131,7 +2439,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       const userLoanInfo = await this.getUserLoanInfo(this.provider.publicKey);
       if (!userLoanInfo) throw "Failed to get user loan info";
@@ -1186,7 +2493,14 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -1226,7 +2540,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       const userLoanInfo = await this.getUserLoanInfo(this.provider.publicKey);
       if (!userLoanInfo) throw "Failed to get user loan info";
@@ -1284,7 +2597,19 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            
+            userStats: web3.PublicKey.findProgramAddressSync(
+            
+              [Buffer.from("user-stats"), user.toBuffer()],
+            
+              this.programId
+            
+            )[0],
+            
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
@@ -1330,7 +2655,6 @@ export class MushiProgramRpc {
       const { token } = globalInfo;
       const mainStateInfo = await this.getMainStateInfo();
       if (!mainStateInfo) throw "Failed to get main state info";
-      const { feeReceiver } = mainStateInfo;
 
       const userLoanInfo = await this.getUserLoanInfo(this.provider.publicKey);
       if (!userLoanInfo) throw "Failed to get user loan info";
@@ -1386,7 +2710,14 @@ export class MushiProgramRpc {
               [Buffer.from("user-loan"), user.toBuffer()],
               this.programId
             )[0],
-            feeReceiver,
+            userStats: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("user-stats"), user.toBuffer()],
+              this.programId
+            )[0],
+            promotion: await this.getPromotion(),
+            referrer: await this.getBoundReferrer(user),
+            integrator: null,
+            launchBuyer: null,
             token,
             userAta,
             tokenVaultOwner: this.vaultOwner,
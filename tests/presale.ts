@@ -0,0 +1,119 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { sleep, MushiProgramRpc, SOL_DECIMALS_HELPER } from "./mushiProgramRpc";
+
+const log = console.log;
+
+// leaf of an allowlist entry: keccak256(wallet || cap in lamports as u64 little endian)
+const presaleLeaf = (wallet: web3.PublicKey, maxBuy: number): Uint8Array => {
+  const lamports = Math.trunc(maxBuy * SOL_DECIMALS_HELPER);
+  const cap = Buffer.alloc(8);
+  cap.writeUInt32LE(lamports % 2 ** 32, 0);
+  cap.writeUInt32LE(Math.floor(lamports / 2 ** 32), 4);
+  return keccak_256(Buffer.concat([wallet.toBuffer(), cap]));
+};
+
+// pairs are hashed sorted, an odd node is carried up as is
+const hashPair = (a: Uint8Array, b: Uint8Array): Uint8Array =>
+  Buffer.compare(Buffer.from(a), Buffer.from(b)) <= 0
+    ? keccak_256(Buffer.concat([a, b]))
+    : keccak_256(Buffer.concat([b, a]));
+
+const merkleRootAndProof = (leaves: Uint8Array[], index: number) => {
+  const proof: number[][] = [];
+  let level = leaves;
+  while (level.length > 1) {
+    const sibling = index ^ 1;
+    if (sibling < level.length) proof.push(Array.from(level[sibling]));
+    const next: Uint8Array[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(i + 1 < level.length ? hashPair(level[i], level[i + 1]) : level[i]);
+    }
+    level = next;
+    index = Math.floor(index / 2);
+  }
+  return { root: Array.from(level[0]), proof };
+};
+
+// launches its own token, so it needs a validator where `start` has not run yet
+describe("mushi_program_presale", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const maxBuy = 0.05;
+  const leaves = [
+    presaleLeaf(web3.Keypair.generate().publicKey, 1),
+    presaleLeaf(provider.publicKey, maxBuy),
+    presaleLeaf(web3.Keypair.generate().publicKey, 0.5),
+  ];
+  const { root, proof } = merkleRootAndProof(leaves, 1);
+
+  before(async () => {
+    if (!(await connectivity.getMainStateInfo())) {
+      const initRes = await connectivity.initialize();
+      if (!initRes.isPass) throw "Failed to init main state";
+      await sleep(5_000);
+    }
+    const globalInfo = await connectivity.getGlobalInfo();
+    if (!globalInfo) throw "Failed to get global info";
+    if (globalInfo.started) throw "Already launched, run on a validator where start has not run";
+
+    const rootRes = await connectivity.updateMainState({ presaleRoot: root });
+    if (!rootRes.isPass) throw "Failed to set the presale root";
+    // the presale runs until trading opens
+    const startRes = await connectivity.start({
+      tokenName: "Mushi",
+      tokenSymbol: "MUSHI",
+      tokenUri: "",
+      solAmount: 1,
+      tradingStartTs: Math.floor(Date.now() / 1000) + 600,
+    });
+    if (!startRes.isPass) throw "Failed to start the launch";
+    await sleep(5_000);
+  });
+
+  it("Presale buys need an allowlist entry", async () => {
+    const presaleRes = await connectivity.presaleBuy(0.01, maxBuy, []);
+    log({ presaleRes: presaleRes.info });
+    if (presaleRes.isPass) throw "Presale buy went through without an allowlist entry";
+    if (connectivity.lastErrorCode !== "NotAllowlisted") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  it("The proof only holds for the allowlisted cap", async () => {
+    const presaleRes = await connectivity.presaleBuy(0.01, 1, proof);
+    if (presaleRes.isPass) throw "Presale buy went through with a larger cap than allowlisted";
+    if (connectivity.lastErrorCode !== "NotAllowlisted") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  it("An allowlisted wallet buys up to its cap", async () => {
+    const before = await connectivity.getGlobalInfo();
+    if (!before) throw "Failed to get global info";
+    const firstRes = await connectivity.presaleBuy(0.03, maxBuy, proof);
+    if (!firstRes.isPass) throw "Allowlisted presale buy failed";
+    await sleep(5_000);
+    const secondRes = await connectivity.presaleBuy(0.02, maxBuy, proof);
+    if (!secondRes.isPass) throw "Presale buy reaching the cap failed";
+    await sleep(5_000);
+
+    const after = await connectivity.getGlobalInfo();
+    if (!after) throw "Failed to get global info";
+    if (after.tokenSupply <= before.tokenSupply) throw "Presale buys minted nothing";
+  });
+
+  it("Buys over the cap fail", async () => {
+    const presaleRes = await connectivity.presaleBuy(0.01, maxBuy, proof);
+    if (presaleRes.isPass) throw "Presale buy went over the cap";
+    if (connectivity.lastErrorCode !== "PresaleCapExceeded") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+});
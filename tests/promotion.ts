@@ -0,0 +1,85 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, fundedKeypair, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+// assumes no fee tier applies to a new user
+describe("mushi_program_promotion", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const promotedBuyTreasuryBps = 50;
+  const buyAmount = 0.5;
+  let userRpc: MushiProgramRpc;
+
+  // treasury fee of a buy by a user without referrer, whose referral part goes to the treasury as well
+  const expectedBuyFee = async (buyTreasuryBps: number | null) => {
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    const { buyReferralBps } = info.feeSchedule;
+    const treasuryBps = Math.min(buyTreasuryBps ?? info.feeSchedule.buyTreasuryBps, info.feeSchedule.buyTreasuryBps);
+    return Math.floor((buyAmount * web3.LAMPORTS_PER_SOL * (treasuryBps + buyReferralBps)) / 10_000);
+  };
+
+  const accruedFeesOfBuy = async () => {
+    const before = await connectivity.getGlobalInfo();
+    if (!before) throw "Failed to get global info";
+    const buyRes = await userRpc.buy(buyAmount);
+    log({ buyRes: buyRes.info });
+    if (!buyRes.isPass) throw "failed to buy tokens";
+    await sleep(5_000);
+    const after = await connectivity.getGlobalInfo();
+    if (!after) throw "Failed to get global info";
+    return after.accruedFees - before.accruedFees;
+  };
+
+  before(async () => {
+    const user = await fundedKeypair(connection, 2);
+    userRpc = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(user), programId });
+  });
+
+  it("Schedule a promotion", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const scheduleRes = await connectivity.schedulePromotion({
+      startTs: now,
+      endTs: now + 60 * 60,
+      buyTreasuryBps: promotedBuyTreasuryBps,
+      sellTreasuryBps: 50,
+      interestFeeBps: 5_000,
+    });
+    log({ scheduleRes: scheduleRes.info });
+    if (!scheduleRes.isPass) throw "failed to schedule promotion";
+    await sleep(5_000);
+  });
+
+  it("Buy during the promotion", async () => {
+    const expected = await expectedBuyFee(promotedBuyTreasuryBps);
+    const charged = await accruedFeesOfBuy();
+    log({ charged, expected });
+    if (charged !== expected) throw `Charged ${charged} instead of the promoted ${expected}`;
+  });
+
+  it("Cancel the promotion", async () => {
+    const cancelRes = await connectivity.cancelPromotion();
+    log({ cancelRes: cancelRes.info });
+    if (!cancelRes.isPass) throw "failed to cancel promotion";
+    await sleep(5_000);
+  });
+
+  it("Buy after the promotion", async () => {
+    const expected = await expectedBuyFee(null);
+    const charged = await accruedFeesOfBuy();
+    log({ charged, expected });
+    if (charged !== expected) throw `Charged ${charged} instead of the full ${expected}`;
+  });
+});
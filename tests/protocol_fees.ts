@@ -0,0 +1,76 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { sleep, fundedKeypair, getInterestFee, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+// assumes no promotion is running, it would lower the interest
+describe("mushi_program_protocol_fees", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const borrowAmount = 0.1;
+  const numberOfDays = 2;
+  let user: web3.Keypair;
+  let connectivity: MushiProgramRpc;
+
+  before(async () => {
+    // a new user has no loan and no referrer taking part of the fees
+    user = await fundedKeypair(connection, 2);
+    connectivity = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(user), programId });
+    const buyRes = await connectivity.buy(0.5);
+    if (!buyRes.isPass) throw "Failed to buy the collateral";
+    await sleep(5_000);
+  });
+
+  it("Borrowing accrues the configured protocol share of the interest", async () => {
+    const info = await connectivity.getMainStateInfo();
+    const before = await connectivity.getGlobalInfo();
+    if (!info || !before) throw "Failed to get state info";
+
+    const borrowRes = await connectivity.borrow(borrowAmount, numberOfDays);
+    if (!borrowRes.isPass) throw "Failed to borrow";
+    await sleep(5_000);
+
+    const after = await connectivity.getGlobalInfo();
+    if (!after) throw "Failed to get global info";
+    const interest = getInterestFee(borrowAmount * web3.LAMPORTS_PER_SOL, numberOfDays);
+    const expected = Math.floor((interest * info.protocolFeeShare) / 1000);
+    const accrued = after.accruedFees - before.accruedFees;
+    log({ interest, protocolFeeShare: info.protocolFeeShare, accrued, expected });
+    if (accrued !== expected) throw `Accrued ${accrued} instead of ${expected}`;
+  });
+
+  it("Flash closing charges the configured fee and accrues its protocol share", async () => {
+    const info = await connectivity.getMainStateInfo();
+    const before = await connectivity.getGlobalInfo();
+    const loan = await connectivity.getUserLoanInfo(user.publicKey);
+    if (!info || !before || !loan) throw "Failed to get state info";
+    // collateral value at the price the program uses, in lamports
+    const collateralInSol = new BN(loan.collateral)
+      .mul(new BN(before.totalBorrowed + before.backing))
+      .div(new BN(before.tokenSupply))
+      .toNumber();
+    const fee = Math.floor((collateralInSol * info.flashCloseFee) / 1000);
+    const expected = Math.floor((fee * info.protocolFeeShare) / 1000);
+
+    const balanceBefore = await connection.getBalance(user.publicKey);
+    const closeRes = await connectivity.flash_close_position();
+    if (!closeRes.isPass) throw "Failed to flash close";
+    await sleep(5_000);
+
+    const after = await connectivity.getGlobalInfo();
+    if (!after) throw "Failed to get global info";
+    const accrued = after.accruedFees - before.accruedFees;
+    log({ collateralInSol, flashCloseFee: info.flashCloseFee, fee, accrued, expected });
+    if (accrued !== expected) throw `Accrued ${accrued} instead of ${expected}`;
+    // the user gets the collateral value less the fee and the loan, and pays the transaction fee
+    const received = (await connection.getBalance(user.publicKey)) - balanceBefore;
+    const toUser = collateralInSol - fee - loan.borrowed;
+    if (received > toUser || received < toUser - 10_000) throw `User received ${received} instead of ${toUser}`;
+  });
+});
@@ -0,0 +1,118 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { sleep, fundedKeypair, MushiProgramRpc, MainStateInfo, ReferrerInfo } from "./mushiProgramRpc";
+
+const log = console.log;
+// assumes no promotion is running and no fee tier applies to a new user
+describe("mushi_program_referral_shares", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const referrer = provider.publicKey;
+  const sellReferralBps = 25;
+  const referralShare = 200;
+  let original: MainStateInfo;
+
+  // a new user bound to the referrer by its first buy
+  const referredUser = async () => {
+    const user = await fundedKeypair(connection, 2);
+    const userRpc = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(user), programId });
+    const buyRes = await userRpc.buy_with_referral(0.5, referrer);
+    if (!buyRes.isPass) throw "Failed to buy with referral";
+    await sleep(5_000);
+    return { user, userRpc };
+  };
+
+  // runs the action and returns what it credited the referrer and accrued to the treasury
+  const creditedBy = async (action: () => Promise<{ isPass: boolean }>) => {
+    const globalBefore = await connectivity.getGlobalInfo();
+    const referrerBefore = await connectivity.getReferrerInfo(referrer);
+    if (!globalBefore || !referrerBefore) throw "Failed to get state info";
+    const res = await action();
+    if (!res.isPass) throw "Referred action failed";
+    await sleep(5_000);
+    const globalAfter = await connectivity.getGlobalInfo();
+    const referrerAfter = await connectivity.getReferrerInfo(referrer);
+    if (!globalAfter || !referrerAfter) throw "Failed to get state info";
+    const delta = (key: keyof ReferrerInfo) => (referrerAfter[key] as number) - (referrerBefore[key] as number);
+    return {
+      reward: delta("pendingRewards"),
+      volume: delta("referredVolume"),
+      accrued: globalAfter.accruedFees - globalBefore.accruedFees,
+    };
+  };
+
+  before(async () => {
+    const info = await connectivity.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    original = info;
+    if (!(await connectivity.getReferrerInfo(referrer))) {
+      const registerRes = await connectivity.registerReferrer();
+      if (!registerRes.isPass) throw "Failed to register referrer";
+      await sleep(5_000);
+    }
+    const updateRes = await connectivity.updateMainState({
+      feeSchedule: { ...info.feeSchedule, sellReferralBps },
+      leverageReferralShare: referralShare,
+      loanReferralShare: referralShare,
+    });
+    if (!updateRes.isPass) throw "Failed to set the referral shares";
+    await sleep(5_000);
+  });
+
+  it("A referred sell pays the referrer its referral fee", async () => {
+    const { user, userRpc } = await referredUser();
+    const info = await connectivity.getMainStateInfo();
+    const token = (await connectivity.getGlobalInfo())?.token;
+    if (!info || !token) throw "Failed to get state info";
+    const balance = await connection.getTokenAccountBalance(getAssociatedTokenAddressSync(token, user.publicKey));
+
+    const credited = await creditedBy(() => userRpc.sell((balance.value.uiAmount ?? 0) / 2));
+    const expected = Math.floor((credited.volume * info.feeSchedule.sellReferralBps) / 10_000);
+    log({ credited, expected });
+    if (credited.volume === 0) throw "Sell was not credited to the referrer";
+    if (credited.reward !== expected) throw `Referrer got ${credited.reward} instead of ${expected}`;
+  });
+
+  it("A referred leverage pays the referrer its share of the protocol fee", async () => {
+    const { userRpc } = await referredUser();
+    const solAmount = 0.2;
+
+    const credited = await creditedBy(() => userRpc.leverage(solAmount, 2));
+    // the protocol fee is split between the referrer and the treasury
+    const expected = Math.floor(((credited.accrued + credited.reward) * referralShare) / 1000);
+    log({ credited, expected });
+    if (credited.volume !== solAmount * web3.LAMPORTS_PER_SOL) throw `Referred volume grew by ${credited.volume}`;
+    if (credited.reward === 0 || credited.reward !== expected) throw `Referrer got ${credited.reward} instead of ${expected}`;
+  });
+
+  it("A referred borrow pays the referrer its share of the protocol fee", async () => {
+    const { userRpc } = await referredUser();
+    const solAmount = 0.1;
+
+    const credited = await creditedBy(() => userRpc.borrow(solAmount, 2));
+    const expected = Math.floor(((credited.accrued + credited.reward) * referralShare) / 1000);
+    log({ credited, expected });
+    if (credited.volume !== solAmount * web3.LAMPORTS_PER_SOL) throw `Referred volume grew by ${credited.volume}`;
+    if (credited.reward === 0 || credited.reward !== expected) throw `Referrer got ${credited.reward} instead of ${expected}`;
+  });
+
+  after(async () => {
+    await connectivity.updateMainState({
+      feeSchedule: original.feeSchedule,
+      leverageReferralShare: original.leverageReferralShare,
+      loanReferralShare: original.loanReferralShare,
+    });
+  });
+});
@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, fundedKeypair, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_referrer", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const authority = provider.publicKey;
+  // unique per run so that the code is never taken by an earlier run
+  const referralCode = `mushi${Date.now().toString(36)}`;
+
+  it("Register referrer", async () => {
+    if (!(await connectivity.getReferrerInfo(authority))) {
+      const registerRes = await connectivity.registerReferrer();
+      if (!registerRes.isPass) throw "Failed to register referrer";
+      log({ registerRes: registerRes.info });
+      await sleep(5_000);
+    }
+    const referrerInfo = await connectivity.getReferrerInfo(authority);
+    if (!referrerInfo || !referrerInfo.authority.equals(authority)) throw "Referrer was not registered";
+  });
+
+  it("Register referral code", async () => {
+    const registerRes = await connectivity.registerReferralCode(referralCode);
+    if (!registerRes.isPass) throw `Failed to register referral code "${referralCode}"`;
+    log({ registerRes: registerRes.info });
+    await sleep(5_000);
+  });
+
+  it("A taken referral code is rejected", async () => {
+    const registerRes = await connectivity.registerReferralCode(referralCode.toUpperCase());
+    if (registerRes.isPass) throw "Registered a referral code that is already taken";
+  });
+
+  it("A buy through the referral code accrues the referral fee", async () => {
+    const info = await connectivity.getMainStateInfo();
+    const before = await connectivity.getReferrerInfo(authority);
+    if (!info || !before) throw "Failed to get state info";
+    const user = await fundedKeypair(connection, 2);
+    const userRpc = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(user), programId });
+    const solAmount = 0.5;
+    const lamports = solAmount * web3.LAMPORTS_PER_SOL;
+
+    const buyRes = await userRpc.buy_with_referral(solAmount, referralCode);
+    if (!buyRes.isPass) throw "Failed to buy through the referral code";
+    await sleep(5_000);
+
+    const after = await connectivity.getReferrerInfo(authority);
+    if (!after) throw "Failed to get referrer info";
+    const expected = Math.floor((lamports * info.feeSchedule.buyReferralBps) / 10_000);
+    log({ before, after, expected });
+    if (after.pendingRewards - before.pendingRewards !== expected)
+      throw `Pending rewards grew by ${after.pendingRewards - before.pendingRewards} instead of ${expected}`;
+    if (after.referredVolume - before.referredVolume !== lamports) throw "Referred volume was not credited";
+    if (after.refereeCount !== before.refereeCount + 1) throw "Referee was not counted";
+  });
+
+  it("Claim referral rewards", async () => {
+    const referrerInfo = await connectivity.getReferrerInfo(authority);
+    if (!referrerInfo) throw "Failed to get referrer info";
+    if (referrerInfo.pendingRewards === 0) throw "No referral rewards to claim";
+    const balanceBefore = await connection.getBalance(authority);
+    const claimRes = await connectivity.claimReferralRewards();
+    if (!claimRes.isPass) throw "Failed to claim referral rewards";
+    log({ claimRes: claimRes.info });
+    await sleep(5_000);
+
+    const after = await connectivity.getReferrerInfo(authority);
+    if (!after) throw "Failed to get referrer info";
+    if (after.pendingRewards !== 0) throw `Pending rewards left after the claim: ${after.pendingRewards}`;
+    // less the transaction fee
+    const received = (await connection.getBalance(authority)) - balanceBefore;
+    if (received > referrerInfo.pendingRewards || received < referrerInfo.pendingRewards - 10_000)
+      throw `Received ${received} instead of ${referrerInfo.pendingRewards}`;
+  });
+});
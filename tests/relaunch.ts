@@ -0,0 +1,46 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_relaunch", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+
+  it("Start can only run once", async () => {
+    const startRes = await connectivity.start({
+      tokenName: "Mushi",
+      tokenSymbol: "MUSHI",
+      tokenUri: "",
+      solAmount: 0.01,
+    });
+    if (startRes.isPass) throw "Started twice";
+  });
+
+  it("Schedule and cancel a relaunch", async () => {
+    const scheduleRes = await connectivity.scheduleRelaunch();
+    if (!scheduleRes.isPass) throw "Failed to schedule relaunch";
+    await sleep(5_000);
+
+    const globalInfo = await connectivity.getGlobalInfo();
+    if (!globalInfo) throw "Failed to get global info";
+    log(`Relaunch executable at ${new Date(globalInfo.relaunchEta * 1000).toISOString()}`);
+
+    const executeRes = await connectivity.executeRelaunch();
+    if (executeRes.isPass) throw "Relaunch executed before its eta";
+
+    const cancelRes = await connectivity.cancelRelaunch();
+    if (!cancelRes.isPass) throw "Failed to cancel relaunch";
+  });
+});
@@ -0,0 +1,73 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+// launches its own token with a team allocation, so it needs a validator where `start` has not run yet
+describe("mushi_program_relaunch_team", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  // 1 SOL at 1e12 base units per SOL mints 1e12 base units, all kept by the vault
+  const teamAllocation = 100_000_000_000;
+
+  before(async () => {
+    if (!(await connectivity.getMainStateInfo())) {
+      const initRes = await connectivity.initialize();
+      if (!initRes.isPass) throw "Failed to init main state";
+      await sleep(5_000);
+    }
+    const globalInfo = await connectivity.getGlobalInfo();
+    if (!globalInfo) throw "Failed to get global info";
+    if (globalInfo.started) throw "Already launched, run on a validator where start has not run";
+
+    const startRes = await connectivity.start({
+      tokenName: "Mushi",
+      tokenSymbol: "MUSHI",
+      tokenUri: "",
+      solAmount: 1,
+      tokensPerSol: 1_000_000_000_000,
+      teamAllocation,
+      teamWallet: provider.publicKey,
+      teamCliffDuration: 3600,
+      teamVestingDuration: 86400,
+    });
+    if (!startRes.isPass) throw "Failed to start the launch";
+    await sleep(5_000);
+
+    const vestingInfo = await connectivity.getTeamVestingInfo();
+    if (!vestingInfo || vestingInfo.total !== teamAllocation) throw "Team allocation was not locked";
+
+    const scheduleRes = await connectivity.scheduleRelaunch();
+    if (!scheduleRes.isPass) throw "Failed to schedule relaunch";
+    await sleep(5_000);
+  });
+
+  it("Without the team vesting its tokens count as outstanding", async () => {
+    const executeRes = await connectivity.executeRelaunch([], false);
+    if (executeRes.isPass) throw "Relaunch executed before its eta";
+    if (connectivity.lastErrorCode !== "SupplyOutstanding") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  it("The unclaimed team allocation does not block the relaunch", async () => {
+    // the supply is checked before the eta, so only the delay is left
+    const executeRes = await connectivity.executeRelaunch();
+    if (executeRes.isPass) throw "Relaunch executed before its eta";
+    log({ errorCode: connectivity.lastErrorCode });
+    if (connectivity.lastErrorCode !== "RelaunchNotReady") throw `Unexpected error ${connectivity.lastErrorCode}`;
+  });
+
+  after(async () => {
+    await connectivity.cancelRelaunch();
+  });
+});
@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_roles", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const pauser = web3.Keypair.generate();
+
+  it("Grant the pauser role", async () => {
+    const grantRes = await connectivity.grantRole("pauser", pauser.publicKey);
+    if (!grantRes.isPass) throw "Failed to grant role";
+    await sleep(5_000);
+
+    const roles = await connectivity.getRoles();
+    if (!roles) throw "Failed to get roles";
+    if (!roles.pauser.equals(pauser.publicKey)) throw "Pauser role not granted";
+    log(`Pauser: ${roles.pauser.toBase58()}`);
+  });
+
+  it("Revoke the pauser role", async () => {
+    const revokeRes = await connectivity.revokeRole("pauser");
+    if (!revokeRes.isPass) throw "Failed to revoke role";
+    await sleep(5_000);
+
+    const roles = await connectivity.getRoles();
+    if (!roles) throw "Failed to get roles";
+    if (!roles.pauser.equals(web3.PublicKey.default)) throw "Pauser role not revoked";
+  });
+
+  it("The fee manager schedules and cancels a promotion", async () => {
+    const feeManager = web3.Keypair.generate();
+    const airdropSig = await connection.requestAirdrop(feeManager.publicKey, web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdropSig);
+    const grantRes = await connectivity.grantRole("feeManager", feeManager.publicKey);
+    if (!grantRes.isPass) throw "Failed to grant role";
+    await sleep(5_000);
+
+    const feeManagerConnectivity = new MushiProgramRpc({
+      rpc,
+      wallet: new anchor.Wallet(feeManager),
+      programId,
+    });
+    const now = Math.floor(Date.now() / 1000);
+    const scheduleRes = await feeManagerConnectivity.schedulePromotion({
+      startTs: now,
+      endTs: now + 3600,
+      buyTreasuryBps: 0,
+      sellTreasuryBps: 0,
+      interestFeeBps: 5000,
+    });
+    if (!scheduleRes.isPass) throw "Fee manager failed to schedule a promotion";
+    await sleep(5_000);
+
+    const cancelRes = await feeManagerConnectivity.cancelPromotion();
+    if (!cancelRes.isPass) throw "Fee manager failed to cancel the promotion";
+
+    const revokeRes = await connectivity.revokeRole("feeManager");
+    if (!revokeRes.isPass) throw "Failed to revoke role";
+  });
+
+  it("A wallet without the fee manager role cannot schedule a promotion", async () => {
+    const stranger = web3.Keypair.generate();
+    const airdropSig = await connection.requestAirdrop(stranger.publicKey, web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdropSig);
+    const strangerConnectivity = new MushiProgramRpc({
+      rpc,
+      wallet: new anchor.Wallet(stranger),
+      programId,
+    });
+    const now = Math.floor(Date.now() / 1000);
+    const scheduleRes = await strangerConnectivity.schedulePromotion({
+      startTs: now,
+      endTs: now + 3600,
+      buyTreasuryBps: 0,
+      sellTreasuryBps: 0,
+      interestFeeBps: 5000,
+    });
+    if (scheduleRes.isPass) throw "Promotion scheduled without the fee manager role";
+  });
+});
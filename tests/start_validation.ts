@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { MushiProgramRpc } from "./mushiProgramRpc";
+
+const MIN_TOKENS_PER_SOL = 1_000_000;
+const MAX_INITIAL_BURN_TOKEN_AMOUNT = 10_000_000_000_000; // 10,000,000 tokens
+const MAX_TEAM_ALLOCATION_BPS = 2_000;
+const MAX_SELLER_FEE_BPS = 1_000;
+const MAX_TRADING_START_DELAY = 30 * 86400;
+
+describe("mushi_program_start_validation", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  // 1 SOL at 1e12 base units per SOL mints 1e12 base units
+  const launch = {
+    tokenName: "Mushi",
+    tokenSymbol: "MUSHI",
+    tokenUri: "",
+    solAmount: 1,
+    tokensPerSol: 1_000_000_000_000,
+  };
+  const initialMint = 1_000_000_000_000;
+
+  // the input is validated before the started check, so these hold before and after the launch
+  const expectStartError = async (input: Parameters<MushiProgramRpc["start"]>[0], errorCode: string) => {
+    const startRes = await connectivity.start(input);
+    if (startRes.isPass) throw `Start accepted an input that should fail with ${errorCode}`;
+    if (connectivity.lastErrorCode !== errorCode)
+      throw `Expected ${errorCode}, got ${connectivity.lastErrorCode}`;
+  };
+
+  it("Price below the minimum fails", async () => {
+    await expectStartError({ ...launch, tokensPerSol: MIN_TOKENS_PER_SOL - 1 }, "InvalidInitialPrice");
+  });
+
+  it("Burn above the initial mint fails", async () => {
+    await expectStartError({ ...launch, burnAmount: initialMint + 1 }, "InvalidBurnAmount");
+  });
+
+  it("Burn above the burn cap fails", async () => {
+    // 1e14 base units minted, more than the cap
+    await expectStartError(
+      { ...launch, tokensPerSol: 100_000_000_000_000, burnAmount: MAX_INITIAL_BURN_TOKEN_AMOUNT + 1 },
+      "InvalidBurnAmount"
+    );
+  });
+
+  it("Team allocation above its share of the initial mint fails", async () => {
+    await expectStartError(
+      {
+        ...launch,
+        teamAllocation: (initialMint * MAX_TEAM_ALLOCATION_BPS) / 10_000 + 1,
+        teamVestingDuration: 86400,
+      },
+      "InvalidTeamAllocation"
+    );
+  });
+
+  it("Team allocation without a vesting duration fails", async () => {
+    await expectStartError({ ...launch, teamAllocation: 1, teamVestingDuration: 0 }, "InvalidVestingSchedule");
+  });
+
+  it("Seller fee above the maximum fails", async () => {
+    await expectStartError({ ...launch, sellerFeeBasisPoints: MAX_SELLER_FEE_BPS + 1 }, "InvalidSellerFee");
+  });
+
+  it("Trading start too far ahead fails", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await expectStartError(
+      { ...launch, tradingStartTs: now + MAX_TRADING_START_DELAY + 3600 },
+      "InvalidLaunchSchedule"
+    );
+  });
+
+  it("Team accounts without a team allocation fail", async () => {
+    await expectStartError({ ...launch, teamWallet: provider.publicKey }, "InvalidInput");
+  });
+
+  it("Team allocation without the team accounts fails", async () => {
+    await expectStartError({ ...launch, teamAllocation: 1, teamVestingDuration: 86400 }, "InvalidInput");
+  });
+});
@@ -0,0 +1,52 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_update_admin", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const coAdmin = web3.Keypair.generate();
+
+  it("Propose and cancel an admin set transfer", async () => {
+    const proposeRes = await connectivity.proposeAdmins([provider.publicKey, coAdmin.publicKey], 2);
+    if (!proposeRes.isPass) throw "Failed to propose admins";
+    await sleep(5_000);
+
+    let mainStateInfo = await connectivity.getMainStateInfo();
+    if (!mainStateInfo) throw "Failed to get main state info";
+    log(`Pending admins: ${mainStateInfo.pendingAdmins.map((admin) => admin.toBase58())}`);
+
+    const cancelRes = await connectivity.cancelAdminTransfer();
+    if (!cancelRes.isPass) throw "Failed to cancel admin transfer";
+    await sleep(5_000);
+
+    mainStateInfo = await connectivity.getMainStateInfo();
+    if (!mainStateInfo) throw "Failed to get main state info";
+    if (mainStateInfo.pendingAdmins.length) throw "Admin transfer was not cancelled";
+  });
+
+  it("Every pending admin has to accept", async () => {
+    const proposeRes = await connectivity.proposeAdmins([provider.publicKey, coAdmin.publicKey], 2);
+    if (!proposeRes.isPass) throw "Failed to propose admins";
+    await sleep(5_000);
+
+    const partialAcceptRes = await connectivity.acceptAdmins([coAdmin]);
+    if (partialAcceptRes.isPass) throw "Admin transfer accepted without every pending admin";
+
+    // leave the current admin in place for the other tests
+    const cancelRes = await connectivity.cancelAdminTransfer();
+    if (!cancelRes.isPass) throw "Failed to cancel admin transfer";
+  });
+});
@@ -0,0 +1,34 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+describe("mushi_program_vesting", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+
+  it("Claim the vested team allocation", async () => {
+    const vestingInfo = await connectivity.getTeamVestingInfo();
+    if (!vestingInfo) throw "No team vesting";
+    log({ vestingInfo });
+
+    const claimRes = await connectivity.claimVested();
+    const isVesting = Date.now() / 1000 >= vestingInfo.cliffTs && vestingInfo.claimed < vestingInfo.total;
+    if (claimRes.isPass !== isVesting) throw "Unexpected claim result";
+    await sleep(5_000);
+
+    const _vestingInfo = await connectivity.getTeamVestingInfo();
+    log({ claimed: _vestingInfo?.claimed });
+  });
+});
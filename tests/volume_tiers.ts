@@ -0,0 +1,111 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { readFileSync } from "fs";
+import { sleep, MushiProgramRpc } from "./mushiProgramRpc";
+
+const log = console.log;
+// The stale window case needs the validator started with a user whose last trade is long past:
+//   --account 49uy2rKKB1Ut2qCSeJxRzSTRrun8ep8JuNaMvY7gS96f tests/fixtures/stale_user_stats.json
+// The fixture is the user stats of stale_volume_user.json, 1000 SOL traded on day 19000 (2022-01-08).
+describe("mushi_program_volume_tiers", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  const rpc = connection.rpcEndpoint;
+  const programId = new web3.PublicKey(
+    "HF5x1bCgynzEnBL7ATMFYPNFjBaqfxgMASyUJL2ud6Xi"
+  );
+  const connectivity = new MushiProgramRpc({
+    rpc,
+    wallet: provider.wallet,
+    programId,
+  });
+  const buyAmount = 0.05;
+  const buyLamports = buyAmount * web3.LAMPORTS_PER_SOL;
+  const discountBps = 5_000;
+
+  // treasury part of a plain buy, the referral part goes to the treasury without a bound referrer
+  const expectedTreasuryFee = async (rpcClient: MushiProgramRpc, user: web3.PublicKey, discount: number) => {
+    const info = await rpcClient.getMainStateInfo();
+    if (!info) throw "Failed to get main state info";
+    const { buyTreasuryBps, buyReferralBps } = info.feeSchedule;
+    const referred = (await rpcClient.getBoundReferrer(user)) !== null;
+    const fee = Math.floor((buyLamports * (referred ? buyTreasuryBps : buyTreasuryBps + buyReferralBps)) / 10_000);
+    return fee - Math.floor((fee * discount) / 10_000);
+  };
+
+  const accruedFeesOfBuy = async (rpcClient: MushiProgramRpc) => {
+    const before = await rpcClient.getGlobalInfo();
+    if (!before) throw "Failed to get global info";
+    const buyRes = await rpcClient.buy(buyAmount);
+    if (!buyRes.isPass) throw "Buy failed";
+    await sleep(5_000);
+    const after = await rpcClient.getGlobalInfo();
+    if (!after) throw "Failed to get global info";
+    return after.accruedFees - before.accruedFees;
+  };
+
+  it("Buys below the tier pay the full treasury fee", async () => {
+    const stats = await connectivity.getUserStatsInfo();
+    const windowVolume = stats?.windowVolume ?? 0;
+    // the next buy reaches the tier exactly
+    const tierRes = await connectivity.updateMainState({
+      feeTiers: [{ minVolume: windowVolume + buyLamports, discountBps }],
+    });
+    if (!tierRes.isPass) throw "Failed to set the fee tiers";
+    await sleep(5_000);
+
+    const expected = await expectedTreasuryFee(connectivity, provider.publicKey, 0);
+    const charged = await accruedFeesOfBuy(connectivity);
+    log({ windowVolume, charged, expected });
+    if (charged !== expected) throw `Charged ${charged} instead of the full ${expected}`;
+  });
+
+  it("Buys once over the tier pay the discounted treasury fee", async () => {
+    const stats = await connectivity.getUserStatsInfo();
+    if (!stats) throw "Failed to get user stats";
+
+    const expected = await expectedTreasuryFee(connectivity, provider.publicKey, discountBps);
+    const charged = await accruedFeesOfBuy(connectivity);
+    log({ windowVolume: stats.windowVolume, charged, expected });
+    if (charged !== expected) throw `Charged ${charged} instead of the discounted ${expected}`;
+  });
+
+  it("Volume older than the window is dropped", async function () {
+    const staleUser = web3.Keypair.fromSecretKey(
+      Uint8Array.from(JSON.parse(readFileSync("tests/fixtures/stale_volume_user.json", "utf8")))
+    );
+    const staleRpc = new MushiProgramRpc({ rpc, wallet: new anchor.Wallet(staleUser), programId });
+    const staleStats = await staleRpc.getUserStatsInfo();
+    // only on a validator loaded with the fixture, and only before its volume was reset
+    if (!staleStats || staleStats.lastTradeDay !== 19000) this.skip();
+    const airdrop = await connection.requestAirdrop(staleUser.publicKey, web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    // the stale 1000 SOL would reach this tier if it were still counted
+    const tierRes = await connectivity.updateMainState({
+      feeTiers: [{ minVolume: 100 * web3.LAMPORTS_PER_SOL, discountBps }],
+    });
+    if (!tierRes.isPass) throw "Failed to set the fee tiers";
+    await sleep(5_000);
+
+    const expected = await expectedTreasuryFee(staleRpc, staleUser.publicKey, 0);
+    const first = await accruedFeesOfBuy(staleRpc);
+    if (first !== expected) throw `First buy charged ${first} instead of the full ${expected}`;
+    // recording the first buy must have cleared the stale day, or it is counted again here
+    const second = await accruedFeesOfBuy(staleRpc);
+    if (second !== expected) throw `Second buy charged ${second} instead of the full ${expected}`;
+
+    const stats = await staleRpc.getUserStatsInfo();
+    if (!stats) throw "Failed to get user stats";
+    log({ stats });
+    const recorded = stats.dailyVolume.reduce((sum, volume) => sum + volume, 0);
+    if (recorded !== 2 * buyLamports) throw `Window holds ${recorded} instead of today's volume`;
+    if (stats.totalVolume !== 1000 * web3.LAMPORTS_PER_SOL + 2 * buyLamports) throw "Total volume was reset";
+  });
+
+  after(async () => {
+    await connectivity.updateMainState({ feeTiers: [] });
+  });
+});